@@ -0,0 +1,29 @@
+const toRange = (location) => ({
+  startLineNumber: location.line,
+  startColumn: location.startColumn,
+  endLineNumber: location.line,
+  endColumn: location.endColumn,
+});
+
+export function makeCodeActionProvider(provide) {
+  return {
+    provideCodeActions: (model, range) => {
+      const versionId = model.getVersionId();
+      const actions = provide(model.getValue(), range.startLineNumber, range.endLineNumber)
+        .map(action => ({
+          title: action.title,
+          kind: 'quickfix',
+          isPreferred: action.preferred,
+          edit: {
+            edits: action.edits.map(edit => ({
+              resource: model.uri,
+              textEdit: { range: toRange(edit.location), text: edit.text },
+              versionId,
+            }))
+          },
+        }));
+
+      return { actions, dispose: () => {} }
+    }
+  }
+}
@@ -1,8 +1,3 @@
-const KEYWORDS = [
-  "ADD", "SUB", "MUL", "DIV", "JUMP", "JMP", "JZ", "JZERO", "JGZ",
-  "JGTZ", "LOAD", "STORE", "INPUT", "READ", "WRITE", "OUTPUT", "HALT"
-];
-
 const CompletionItemKind = {
   Method: 0,
   Function: 1,
@@ -34,20 +29,36 @@ const CompletionItemKind = {
   Snippet: 27,
 }
 
-export function completionItemsProvider() {
+const InsertAsSnippet = 4;
+const TriggerCharacter = 1;
+
+export function makeCompletionItemsProvider(provide) {
   return {
-    provideCompletionItems: (model, position) => {
-      let c = model.getValue().split('\n')[position.lineNumber - 1][position.column - 2];
-      let keywords = KEYWORDS.slice(0);
+    triggerCharacters: [' '],
+    provideCompletionItems: (model, position, context) => {
+      // Space only triggers suggestions after an opcode or a label, not while indenting
+      const before = model.getLineContent(position.lineNumber).slice(0, position.column - 1);
+      if (context.triggerKind === TriggerCharacter && before.trim() === '') {
+        return { suggestions: [] };
+      }
 
-      if (c >= 'a' && c <= 'z')
-        keywords = keywords.map(s => s.toLowerCase());
+      const word = model.getWordUntilPosition(position);
+      const range = {
+        startLineNumber: position.lineNumber,
+        startColumn: word.startColumn,
+        endLineNumber: position.lineNumber,
+        endColumn: word.endColumn,
+      };
 
       return {
-        suggestions: keywords.map(keyword => ({
-          label: keyword,
-          kind: CompletionItemKind.Keyword,
-          insertText: keyword,
+        suggestions: provide(model.getValue(), position.lineNumber, position.column).map(item => ({
+          label: item.label,
+          kind: CompletionItemKind[item.kind],
+          insertText: item.insertText,
+          insertTextRules: item.snippet ? InsertAsSnippet : 0,
+          detail: item.detail,
+          documentation: { value: item.documentation },
+          range,
         }))
       }
     }
@@ -0,0 +1,8 @@
+export function makeFormattingProvider(format) {
+  return {
+    provideDocumentFormattingEdits: (model) => [{
+      range: model.getFullModelRange(),
+      text: format(model.getValue()),
+    }]
+  }
+}
@@ -0,0 +1,18 @@
+export function makeHoverProvider(provide) {
+  return {
+    provideHover: (model, position) => {
+      const hover = provide(model.getValue(), position.lineNumber, position.column);
+      if (!hover) return null;
+
+      return {
+        range: {
+          startLineNumber: position.lineNumber,
+          startColumn: hover.startColumn,
+          endLineNumber: position.lineNumber,
+          endColumn: hover.endColumn,
+        },
+        contents: hover.contents.map(value => ({ value })),
+      }
+    }
+  }
+}
@@ -0,0 +1,25 @@
+const toLocation = (model, location) => ({
+  uri: model.uri,
+  range: {
+    startLineNumber: location.line,
+    startColumn: location.startColumn,
+    endLineNumber: location.line,
+    endColumn: location.endColumn,
+  },
+});
+
+export function makeDefinitionProvider(provide) {
+  return {
+    provideDefinition: (model, position) =>
+      provide(model.getValue(), position.lineNumber, position.column)
+        .map(location => toLocation(model, location))
+  }
+}
+
+export function makeReferenceProvider(provide) {
+  return {
+    provideReferences: (model, position, context) =>
+      provide(model.getValue(), position.lineNumber, position.column, context.includeDeclaration)
+        .map(location => toLocation(model, location))
+  }
+}
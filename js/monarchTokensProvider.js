@@ -1,9 +1,5 @@
-const keywords = [
-  "ADD", "SUB", "MUL", "DIV", "JUMP", "JMP", "JZ", "JZERO", "JGZ",
-  "JGTZ", "LOAD", "STORE", "INPUT", "READ", "WRITE", "OUTPUT", "HALT"
-];
-
-export function makeTokensProvider() {
+// Keywords come from the instruction table in Rust
+export function makeTokensProvider(keywords) {
   return {
     keywords: keywords,
     ignoreCase: true,
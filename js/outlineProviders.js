@@ -0,0 +1,36 @@
+const SymbolKind = {
+  Function: 11,
+};
+
+export function makeDocumentSymbolProvider(provide) {
+  return {
+    provideDocumentSymbols: (model) =>
+      provide(model.getValue()).map(block => ({
+        name: block.label,
+        detail: block.detail,
+        kind: SymbolKind.Function,
+        tags: [],
+        range: {
+          startLineNumber: block.location.line,
+          startColumn: 1,
+          endLineNumber: block.endLine,
+          endColumn: model.getLineMaxColumn(block.endLine),
+        },
+        selectionRange: {
+          startLineNumber: block.location.line,
+          startColumn: block.location.startColumn,
+          endLineNumber: block.location.line,
+          endColumn: block.location.endColumn,
+        },
+      }))
+  }
+}
+
+export function makeFoldingRangeProvider(provide) {
+  return {
+    provideFoldingRanges: (model) =>
+      provide(model.getValue())
+        .filter(block => block.endLine > block.location.line)
+        .map(block => ({ start: block.location.line, end: block.endLine }))
+  }
+}
@@ -0,0 +1,30 @@
+const toRange = (location) => ({
+  startLineNumber: location.line,
+  startColumn: location.startColumn,
+  endLineNumber: location.line,
+  endColumn: location.endColumn,
+});
+
+export function makeRenameProvider(resolve, rename) {
+  return {
+    resolveRenameLocation: (model, position) => {
+      const result = resolve(model.getValue(), position.lineNumber, position.column);
+      if (result.rejectReason) return result;
+
+      return { range: toRange(result.location), text: result.text };
+    },
+    provideRenameEdits: (model, position, newName) => {
+      const result = rename(model.getValue(), position.lineNumber, position.column, newName);
+      if (result.rejectReason) return result;
+
+      const versionId = model.getVersionId();
+      return {
+        edits: result.locations.map(location => ({
+          resource: model.uri,
+          textEdit: { range: toRange(location), text: newName },
+          versionId,
+        }))
+      }
+    }
+  }
+}
@@ -1,5 +1,7 @@
 use yew::prelude::*;
 
+use crate::monaco_ram::INSTRUCTIONS;
+
 #[derive(Clone, PartialEq, Properties)]
 pub struct Props {
     pub popup_ref: NodeRef,
@@ -13,6 +15,16 @@ pub fn about_popup(props: &Props) -> Html {
     let ram_cli = html! { <a href="https://github.com/AVO-cado-team/ram-cli">{"ram-cli"}</a> };
     let ram_webgui = html! { <a href="https://github.com/AVO-cado-team/ram-webgui">{"ram-webgui"}</a> };
 
+    let instructions = INSTRUCTIONS.iter().map(|instruction| {
+        html! {
+          <tr>
+            <td class="instruction-name">{instruction.names().collect::<Vec<_>>().join(" / ")}</td>
+            <td>{inline_code(&instruction.operand_forms())}</td>
+            <td>{inline_code(instruction.description)}</td>
+          </tr>
+        }
+    });
+
     let popup = html! {
       <div class="about-popup" ref={&props.popup_ref}>
           <div class="about-us-block">
@@ -44,6 +56,13 @@ pub fn about_popup(props: &Props) -> Html {
                     </div>
                 </div>
 
+                <div class="instruction-reference">
+                    <h3>{ "Instructions:" }</h3>
+                    <table>
+                      { for instructions }
+                    </table>
+                </div>
+
                 <div class="avo-block">
                     <a href="https://github.com/AVO-cado-team">
                       <img src="assets/github-mark-white.png" alt="avo" class="avo"/>
@@ -57,3 +76,17 @@ pub fn about_popup(props: &Props) -> Html {
 
     create_portal(popup, body.into())
 }
+
+/// Renders the `code` spans of the instruction table's Markdown.
+fn inline_code(text: &str) -> Html {
+    text.split('`')
+        .enumerate()
+        .map(|(index, part)| {
+            if index % 2 == 1 {
+                html! { <code>{part}</code> }
+            } else {
+                html! { {part} }
+            }
+        })
+        .collect()
+}
@@ -141,6 +141,12 @@ impl Component for App {
                     url += &author;
                 }
 
+                for (name, stdin) in &self.store.stdin_presets {
+                    let name = urlencoding::encode(name);
+                    let stdin = urlencoding::encode(stdin);
+                    url += &format!("&preset={name}:{stdin}");
+                }
+
                 log::debug!("Copying to clipboard: {}", url);
                 copy_to_clipboard(&url);
             }
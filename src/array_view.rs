@@ -0,0 +1,111 @@
+use ramemu::registers::RegisterId;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yewdux::use_selector;
+
+use crate::store::{RunStore, Store};
+
+const DEFAULT_RANGE: (usize, usize) = (10, 30);
+const MAX_LENGTH: usize = 256;
+
+/// Inclusive bounds of the registers shown for the typed `from` and `to`,
+/// in either order and at most [`MAX_LENGTH`] of them.
+fn visible_range(from: usize, to: usize) -> (usize, usize) {
+    let (first, last) = (from.min(to), from.max(to));
+    (first, last.min(first.saturating_add(MAX_LENGTH - 1)))
+}
+
+/// Renders a register range as an array strip, with pointer registers listed
+/// next to the cells they currently address.
+#[function_component]
+pub fn ArrayView() -> Html {
+    let registers = use_selector(|s: &RunStore| s.registers.clone());
+    let pointers = use_selector(|s: &Store| s.pointer_registers.clone());
+    let range = use_state_eq(|| DEFAULT_RANGE);
+
+    let (from, to) = *range;
+    let (first, last) = visible_range(from, to);
+
+    let values: Vec<_> = (first..=last)
+        .map(|index| registers.get(RegisterId(index)))
+        .collect();
+    let scale = values
+        .iter()
+        .map(|value| value.unsigned_abs())
+        .max()
+        .unwrap_or_default()
+        .max(1) as f64;
+
+    let cells = (first..=last).zip(&values).map(|(index, &value)| {
+        let width = value.unsigned_abs() as f64 / scale * 100.;
+        let arrows = pointers
+            .iter()
+            .filter(|&&pointer| registers.get(RegisterId(pointer)) == index as i64)
+            .map(|pointer| format!("R{pointer}"))
+            .collect::<Vec<_>>();
+        let bar_class = classes!("array-bar", (value < 0).then_some("negative"));
+
+        html! {
+          <div class={classes!("array-cell", (!arrows.is_empty()).then_some("pointed"))} key={index}>
+            <span class="array-index">{index}</span>
+            <div class="array-bar-track">
+              <div class={bar_class} style={format!("width: {width:.1}%")} />
+            </div>
+            <span class="array-value">{value}</span>
+            if !arrows.is_empty() {
+              <span class="array-pointers">{"← "}{arrows.join(", ")}</span>
+            }
+          </div>
+        }
+    });
+
+    let on_bound_change = |set: fn(&mut (usize, usize), usize)| {
+        let range = range.clone();
+        Callback::from(move |event: Event| {
+            let Some(input) = event.target_dyn_into::<HtmlInputElement>() else {
+                log::error!("Failed to cast event target to HtmlInputElement");
+                return;
+            };
+            let Ok(bound) = input.value().trim().parse() else {
+                return;
+            };
+            let mut new_range = *range;
+            set(&mut new_range, bound);
+            range.set(new_range);
+        })
+    };
+    let on_from_change = on_bound_change(|range, from| range.0 = from);
+    let on_to_change = on_bound_change(|range, to| range.1 = to);
+
+    html! {
+      <div class="array-view">
+        <div class="array-range">
+          {"R"}
+          <input type="number" min="0" value={from.to_string()} onchange={on_from_change} />
+          {".."}
+          {"R"}
+          <input type="number" min="0" value={to.to_string()} onchange={on_to_change} />
+        </div>
+        <div class="array-cells">{ for cells }</div>
+      </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_is_ordered_and_capped() {
+        assert_eq!(visible_range(30, 10), (10, 30));
+        assert_eq!(visible_range(0, 1000), (0, MAX_LENGTH - 1));
+        assert_eq!(
+            visible_range(usize::MAX, usize::MAX),
+            (usize::MAX, usize::MAX)
+        );
+        assert_eq!(
+            visible_range(usize::MAX - 1, usize::MAX),
+            (usize::MAX - 1, usize::MAX)
+        );
+    }
+}
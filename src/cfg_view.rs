@@ -0,0 +1,75 @@
+use web_sys::Element;
+use yew::prelude::*;
+use yewdux::use_selector;
+
+use crate::{
+    language::{cfg::Program, diagnostics::diagnostics},
+    store::{dispatch, Store},
+    utils::{download_file, select_lines},
+};
+
+/// Control-flow graph of the program in the editor. Clicking a block selects
+/// its lines, and the block of the current debug line is highlighted.
+#[function_component]
+pub fn CfgView() -> Html {
+    let version = *use_selector(|s: &Store| s.model_version());
+    let current_line = *use_selector(|s: &Store| s.current_debug_line);
+    let code = use_memo(version, |_| dispatch().get().get_model().get_value());
+    let parses = use_memo(version, |_| diagnostics(&code).is_empty());
+    let current_line = (current_line != 0).then_some(current_line);
+    let svg = use_memo((version, current_line), |_| {
+        Program::new(&code).to_svg(current_line)
+    });
+
+    if !*parses {
+        return html! {
+          <div class="cfg-view cfg-message">{"Fix the errors in the code to see its graph"}</div>
+        };
+    }
+
+    let onclick = |event: MouseEvent| {
+        let block = event
+            .target_dyn_into::<Element>()
+            .and_then(|target| target.closest("[data-first-line]").ok().flatten());
+        let Some(block) = block else {
+            return;
+        };
+        let line = |name: &str| block.get_attribute(name)?.parse::<usize>().ok();
+        if let Some((first, last)) = line("data-first-line").zip(line("data-last-line")) {
+            select_lines(first, last);
+        }
+    };
+
+    let on_export_dot = {
+        let code = code.clone();
+        move |_: MouseEvent| {
+            if let Err(err) = download_file(&Program::new(&code).to_dot(), "graph.dot") {
+                gloo::console::error!("Failed to download graph: ", err);
+            }
+        }
+    };
+    let on_export_svg = {
+        let code = code.clone();
+        move |_: MouseEvent| {
+            if let Err(err) = download_file(&Program::new(&code).to_svg(None), "graph.svg") {
+                gloo::console::error!("Failed to download graph: ", err);
+            }
+        }
+    };
+
+    html! {
+      <div class="cfg-view">
+        <div class="cfg-export">
+          <button class="memory-btn" onclick={on_export_dot} title="Download as Graphviz DOT">
+            {"DOT"}
+          </button>
+          <button class="memory-btn" onclick={on_export_svg} title="Download as SVG">
+            {"SVG"}
+          </button>
+        </div>
+        <div class="cfg-graph" {onclick}>
+          {Html::from_html_unchecked(AttrValue::from((*svg).clone()))}
+        </div>
+      </div>
+    }
+}
@@ -1,4 +1,9 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::{
+    cell::RefCell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gloo::timers::callback::Timeout;
 
 use js_sys::Object;
 use monaco::{
@@ -14,14 +19,18 @@ use wasm_bindgen::{closure::Closure, JsCast};
 use yew::prelude::*;
 
 use crate::{
+    jump_arrows::JumpArrows,
     monaco_ram::{register_ram, LANG_ID, THEME},
-    monaco_tweaks::setup_breakpoints,
+    monaco_tweaks::{publish_diagnostics, setup_breakpoints},
     store::{dispatch, Store},
-    utils::{comment_code, download_code},
+    utils::{comment_code, download_code, format_code},
 };
 
 type JsCallback = Closure<dyn Fn()>;
 
+/// Typing pause after which the model is parsed again for diagnostics.
+const DIAGNOSTICS_DELAY_MS: u32 = 300;
+
 pub const DEFAULT_CODE: &str = r"
 read 1
 write 1
@@ -37,7 +46,7 @@ pub fn get_editor_options(read_only: bool) -> IStandaloneEditorConstructionOptio
         .with_automatic_layout(true)
         .to_sys_options();
 
-    let hover_options = Object::new().unchecked_into::<IEditorHoverOptions>(); // Doesn't work
+    let hover_options = Object::new().unchecked_into::<IEditorHoverOptions>();
     hover_options.set_enabled(Some(true));
     hover_options.set_delay(Some(300.));
 
@@ -46,7 +55,7 @@ pub fn get_editor_options(read_only: bool) -> IStandaloneEditorConstructionOptio
     options.set_read_only(Some(read_only));
     options.set_glyph_margin(Some(true));
     options.set_line_numbers(Some(editor::LineNumbersType::Relative));
-    options.set_hover(Some(&hover_options)); // Doesn't work
+    options.set_hover(Some(&hover_options));
     options
 }
 
@@ -81,6 +90,7 @@ impl Component for CustomEditor {
 
         html! {
           <div id="container" class="editor-container" ref={&self.editor_ref}>
+              <JumpArrows />
               <CodeEditor
                 classes={"editor"}
                 options={get_editor_options(read_only)}
@@ -99,15 +109,23 @@ impl Component for CustomEditor {
         let editor_ref: NodeRef = Default::default();
 
         let text_model = dispatch().get().get_model().clone();
+        // Replacing the pending timeout cancels it, so only the last change is parsed
+        let pending_diagnostics = RefCell::new(None);
         let text_model_saver = text_model.on_did_change_content(move |_| {
             dispatch().reduce_mut(move |s: &mut Store| s.change_model());
+            let timeout = Timeout::new(DIAGNOSTICS_DELAY_MS, || {
+                publish_diagnostics(dispatch().get().get_model());
+            });
+            pending_diagnostics.replace(Some(timeout));
         });
         std::mem::forget(text_model_saver);
 
         let search = gloo::utils::window().location().search();
         let search = search.unwrap_or_default().replace('?', "");
         let search = search.split('&');
-        let search = search.filter(|x| x.starts_with("code=") || x.starts_with("=stdin"));
+        let search = search.filter(|x| {
+            x.starts_with("code=") || x.starts_with("stdin=") || x.starts_with("preset=")
+        });
 
         for entry in search {
             if let Some(code_in_url) = entry.strip_prefix("code=") {
@@ -117,11 +135,34 @@ impl Component for CustomEditor {
                 }
             } else if let Some(stdin) = entry.strip_prefix("stdin=") {
                 if let Ok(stdin) = urlencoding::decode(&stdin) {
-                    dispatch().reduce_mut(|s: &mut Store| s.stdin = stdin.to_string());
+                    dispatch().reduce_mut(|s: &mut Store| s.set_stdin(stdin.into_owned()));
+                }
+            } else if let Some((name, stdin)) = entry
+                .strip_prefix("preset=")
+                .and_then(|preset| preset.split_once(':'))
+            {
+                if let Ok((name, stdin)) = urlencoding::decode(name).and_then(|name| {
+                    let stdin = urlencoding::decode(stdin)?;
+                    Ok((name.into_owned(), stdin.into_owned()))
+                }) {
+                    let local = dispatch().get().stdin_presets.get(&name).cloned();
+                    let replace = local.map_or(true, |local| {
+                        local == stdin
+                            || gloo::dialogs::confirm(&format!(
+                                "The link shares an input preset named \"{name}\", but you already have a different one with that name. Replace yours?"
+                            ))
+                    });
+                    if replace {
+                        dispatch().reduce_mut(|s: &mut Store| {
+                            s.stdin_presets.insert(name, stdin);
+                        });
+                    }
                 }
             }
         }
 
+        publish_diagnostics(&text_model);
+
         Self { editor_ref }
     }
 
@@ -143,6 +184,13 @@ impl Component for CustomEditor {
 
         match msg {
             Msg::DownloadCode => {
+                let store = dispatch().get();
+                if store.format_options.format_on_save {
+                    let options = store.format_options;
+                    store
+                        .editor
+                        .with_editor(|editor| format_code(editor, &text_model, options));
+                }
                 if let Err(err) = download_code(&text_model.get_value()) {
                     gloo::console::error!("Failed to download code: ", err);
                 }
@@ -1,16 +1,18 @@
 use std::rc::Rc;
 
-use crate::io::custom_reader::CustomReader;
-use crate::io::custom_writer::CustomWriter;
+use crate::io::custom_reader::{CustomReader, ReadPosition};
+use crate::io::custom_writer::{CustomWriter, OutputEntry, OutputLog, WriteOrigin};
 use crate::io::input::InputComponent;
 use crate::io::output::OutputComponent;
 use crate::io::output::OutputComponentErrors;
-use crate::store::dispatch;
-use crate::store::Store;
+use crate::register_history::RegisterHistory;
+use crate::store::{dispatch, run_dispatch, RunStore, Store};
+use crate::syntax::pointer_registers;
 
 use ramemu::parser::parse;
 use ramemu::ram::Ram;
 use ramemu::ram::RamState;
+use ramemu::registers::{RegisterId, Registers};
 
 use yew::prelude::*;
 use yewdux::Dispatch;
@@ -28,7 +30,7 @@ const DELAY_BETWEEN_STEPS: Duration = Duration::from_millis(10);
 const DELAY_BETWEEN_STEPS: Duration = Duration::from_millis(10);
 
 pub enum Msg {
-    WriterWrote(String),
+    WriterWrote(Vec<OutputEntry>),
     DebugAction(DebugAction),
     SetStore(Rc<Store>),
 }
@@ -59,9 +61,16 @@ pub struct Props {
 }
 
 pub struct CodeRunner {
-    stdout: String,
+    stdout: OutputLog,
     writer: CustomWriter,
     debug: State,
+    history: RegisterHistory,
+    read_position: ReadPosition,
+    registers: Registers<i64>,
+    steps: usize,
+    runs: usize,
+    line: usize,
+    last_step: Option<(usize, usize)>,
     store: Rc<Store>,
     _dispatch: Dispatch<Store>,
 }
@@ -74,7 +83,9 @@ impl Component for CodeRunner {
         let errors = self.store.errors.clone();
         html! {
             <div class="console-container">
+              // Keyed by run, so what the console shows is reset for each one
               <OutputComponent
+                key={self.runs}
                 {errors}
                 output={self.stdout.clone()}
               />
@@ -95,6 +106,13 @@ impl Component for CodeRunner {
             debug: None,
             stdout: Default::default(),
             writer: CustomWriter::new(ctx.link().callback(Msg::WriterWrote)),
+            history: Default::default(),
+            read_position: Default::default(),
+            registers: Default::default(),
+            steps: 0,
+            runs: 0,
+            line: 0,
+            last_step: None,
             store: dispatch.get(),
             _dispatch: dispatch,
         }
@@ -117,9 +135,8 @@ impl Component for CodeRunner {
                 return true;
             }
             Msg::DebugAction(action) => action,
-            Msg::WriterWrote(data) => {
-                self.stdout.push_str(&data);
-                self.stdout.push('\n');
+            Msg::WriterWrote(entries) => {
+                self.stdout.extend(entries);
                 return true;
             }
         };
@@ -147,7 +164,9 @@ impl CodeRunner {
     fn debug_start(&mut self, ctx: &Context<Self>, action: DebugAction) -> State {
         log::info!("Debug Start");
 
-        self.stdout.clear();
+        self.stdout = OutputLog::default();
+        self.runs += 1;
+        self.writer.discard();
 
         let (code, message, state) = match action {
             DebugAction::Start(code) => (
@@ -159,15 +178,37 @@ impl CodeRunner {
             state => panic!("Called `debug_start` in {state:?}"),
         };
 
+        let mode = self.store.io_mode;
+        let invalid_input: Vec<_> = CustomReader::invalid_values(&self.store.stdin, mode)
+            .map(str::to_owned)
+            .collect();
+        if !invalid_input.is_empty() && !self.store.allow_invalid_stdin {
+            dispatch().reduce_mut(move |store: &mut Store| {
+                store.errors = vec![OutputComponentErrors::InvalidInput(invalid_input)];
+            });
+            return None;
+        }
+
         match parse(&code) {
             Ok(program) => {
-                let ram = Ram::new(
-                    program,
-                    Box::new(CustomReader::new(&self.store.stdin)),
-                    Box::new(self.writer.clone()),
-                );
-
-                dispatch().reduce_mut(|s: &mut Store| s.read_only = true);
+                let reader = CustomReader::new(&self.store.stdin, mode);
+                self.read_position = reader.position();
+                let ram = Ram::new(program, Box::new(reader), Box::new(self.writer.clone()));
+
+                let initial: RamState = ram.as_ref().into();
+                self.history = RegisterHistory::new(&code);
+                self.registers = Registers::default();
+                self.steps = 0;
+                self.line = initial.line;
+                self.last_step = None;
+
+                let pointer_registers = pointer_registers(&code);
+                self.publish_run(false);
+                dispatch().reduce_mut(|s: &mut Store| {
+                    s.read_only = true;
+                    s.inspected_step = None;
+                    s.pointer_registers = pointer_registers;
+                });
 
                 ctx.link().send_message(Msg::DebugAction(message));
                 Some((state, ram))
@@ -184,17 +225,53 @@ impl CodeRunner {
         }
     }
 
-    #[allow(clippy::unnecessary_wraps, clippy::unused_self)]
-    fn debug_step(&self, ctx: &Context<Self>, mut ram: Ram) -> State {
+    fn publish_run(&self, paused: bool) {
+        run_dispatch().set(RunStore {
+            registers: self.registers.clone(),
+            stdin_position: Some(self.read_position.get()),
+            paused,
+        });
+    }
+
+    /// Executes the instruction on `self.line`, tagging what it writes to
+    /// registers and stdout. Returns the line execution continues on.
+    fn next_state(&mut self, ram: &mut Ram) -> Option<usize> {
+        let origin = WriteOrigin {
+            step: self.steps + 1,
+            line: self.line,
+        };
+        self.writer.set_origin(origin);
+
+        let target = self.history.target(origin.line, &self.registers);
+        let state = ram.next()?;
+        self.registers = state.registers;
+        self.history.record_step(origin.line);
+        if let Some(register) = target {
+            let value = self.registers.get(RegisterId(register));
+            self.history
+                .record(origin.step, origin.line, register, value);
+        }
+        self.steps = origin.step;
+        self.line = state.line;
+        self.last_step = Some((origin.line, state.line));
+
+        Some(state.line)
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn debug_step(&mut self, ctx: &Context<Self>, mut ram: Ram) -> State {
         log::debug!("Debug Step");
 
-        match ram.next() {
-            Some(state) => {
-                let registers = state.registers;
-                let line = state.line;
+        match self.next_state(&mut ram) {
+            Some(line) => {
+                self.publish_run(true);
+                let history = self.history.clone();
+                let last_step = self.last_step;
                 dispatch().reduce_mut(|s: &mut Store| {
-                    s.set_registers(registers);
                     s.current_debug_line = line;
+                    s.inspected_step = None;
+                    s.last_step = last_step;
+                    s.register_history = history;
                 });
             }
             None => ctx.link().send_message(Msg::DebugAction(DebugAction::Stop)),
@@ -203,15 +280,16 @@ impl CodeRunner {
         Some((Pause, ram))
     }
 
-    #[allow(clippy::unnecessary_wraps, clippy::unused_self)]
-    fn debug_continue(&self, ctx: &Context<Self>, mut ram: Ram) -> State {
+    #[allow(clippy::unnecessary_wraps)]
+    fn debug_continue(&mut self, ctx: &Context<Self>, mut ram: Ram) -> State {
         log::debug!("Debug Continue");
 
+        let next = self.next_state(&mut ram);
         let breakpoints = &self.store.breakpoints;
         let kind;
 
-        match ram.next() {
-            Some(state) if breakpoints.contains(&state.line) => kind = Pause,
+        match next {
+            Some(line) if breakpoints.contains(&line) => kind = Pause,
             None => {
                 ctx.link().send_message(Msg::DebugAction(DebugAction::Stop));
                 return Some((Pause, ram)); // Not `None` to give `debug_stop` a state to work with
@@ -228,14 +306,19 @@ impl CodeRunner {
             }),
         };
 
+        // Only what changes on every step is published while running, so the
+        // store isn't saved to local storage each time
+        self.publish_run(kind == Pause);
         if kind == Pause {
-            let state: RamState = ram.as_ref().into();
-            let registers = state.registers;
-            let line = state.line;
+            let line = self.line;
+            let history = self.history.clone();
+            let last_step = self.last_step;
 
             dispatch().reduce_mut(|s: &mut Store| {
-                s.set_registers(registers);
                 s.current_debug_line = line;
+                s.inspected_step = None;
+                s.last_step = last_step;
+                s.register_history = history;
             });
         }
 
@@ -249,13 +332,16 @@ impl CodeRunner {
 
         let state: RamState = ram.into();
 
-        let registers = state.registers;
+        self.registers = state.registers;
+        self.publish_run(false);
         let error = state.error;
+        let history = self.history.clone();
 
         dispatch().reduce_mut(|s: &mut Store| {
-            s.set_registers(registers);
+            s.register_history = history;
             s.read_only = false;
             s.current_debug_line = 0;
+            s.last_step = None;
             s.errors = error
                 .map(OutputComponentErrors::InterpretError)
                 .into_iter()
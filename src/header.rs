@@ -4,7 +4,7 @@ use web_sys::Node;
 use yew::prelude::*;
 use yewdux::use_selector;
 
-use crate::{about_popup::AboutPopup, store::Store, utils::get_author};
+use crate::{about_popup::AboutPopup, settings::Settings, store::Store, utils::get_author};
 
 #[derive(Default, Clone, PartialEq, Properties)]
 pub struct Props {
@@ -104,6 +104,7 @@ pub fn header(props: &Props) -> Html {
             </button>
           </div>
           <div class="help">
+            <Settings />
             <button
               onclick={move |_| show_popup.set(!*show_popup)}
               class="about-us"
@@ -1,24 +1,64 @@
-use std::io::{BufRead, Error, ErrorKind, Read, Result};
+use std::{
+    cell::Cell,
+    io::{BufRead, Error, ErrorKind, Read, Result},
+    rc::Rc,
+};
 
 use serde::{Deserialize, Serialize};
 
+use crate::io::{input_dsl, io_mode::IoMode};
+
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CustomReader {
     input: String,
+    #[serde(skip)]
+    position: ReadPosition,
+}
+
+/// Number of values consumed from a [`CustomReader`], observable after the
+/// reader itself was handed over to the `Ram`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ReadPosition(Rc<Cell<usize>>);
+
+impl ReadPosition {
+    pub fn get(&self) -> usize {
+        self.0.get()
+    }
 }
 
 impl CustomReader {
-    pub fn new(input: &str) -> Self {
+    pub fn new(input: &str, mode: IoMode) -> Self {
         let mut reader = Self::default();
-        reader.set_input(input);
+        reader.set_input(input, mode);
         reader
     }
 
-    pub fn set_input(&mut self, input: &str) {
+    pub fn set_input(&mut self, input: &str, mode: IoMode) {
         // NOTE: there should be '\n' at the end of input, so be aware of that behavior
-        self.input = input
-            .split_whitespace()
-            .fold(String::new(), |a, b| a + b + "\n");
+        self.input = Self::values(input, mode)
+            .into_iter()
+            .fold(String::new(), |a, b| a + &b + "\n");
+        self.position.0.set(0);
+    }
+
+    /// Expands `input` into the values `READ` will consume, in order.
+    /// See [`input_dsl`] for the accepted terms of [`IoMode::Numbers`].
+    pub fn values(input: &str, mode: IoMode) -> Vec<String> {
+        match mode {
+            IoMode::Numbers => input_dsl::expand(input),
+            IoMode::Characters => input.chars().map(|c| u32::from(c).to_string()).collect(),
+        }
+    }
+
+    /// Tokens of `input` that are neither integers nor input language terms.
+    /// Any text is valid in [`IoMode::Characters`].
+    pub fn invalid_values(input: &str, mode: IoMode) -> impl Iterator<Item = &str> {
+        input_dsl::tokens(input)
+            .filter(move |token| mode == IoMode::Numbers && !input_dsl::is_valid(token))
+    }
+
+    pub fn position(&self) -> ReadPosition {
+        self.position.clone()
     }
 }
 
@@ -45,6 +85,8 @@ impl BufRead for CustomReader {
     }
 
     fn consume(&mut self, amt: usize) {
-        self.input.drain(..amt.min(self.input.as_bytes().len()));
+        let consumed = self.input.drain(..amt.min(self.input.as_bytes().len()));
+        let values = consumed.filter(|&c| c == '\n').count();
+        self.position.0.set(self.position.get() + values);
     }
 }
@@ -1,15 +1,124 @@
-use std::io::Write;
+use std::{
+    cell::{Cell, Ref, RefCell},
+    io::Write,
+    rc::Rc,
+};
 
+use gloo::{
+    render::{request_animation_frame, AnimationFrame},
+    timers::callback::Timeout,
+};
 use yew::Callback;
 
-#[derive(Debug, Default, Clone, PartialEq)]
+/// Where in the run a value was written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOrigin {
+    pub step: usize,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputEntry {
+    pub value: String,
+    pub origin: WriteOrigin,
+}
+
+/// Append-only output shared between the runner and the console, so growing
+/// it doesn't copy every previous entry.
+#[derive(Debug, Default, Clone)]
+pub struct OutputLog {
+    entries: Rc<RefCell<Vec<OutputEntry>>>,
+    len: usize,
+}
+
+impl PartialEq for OutputLog {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.entries, &other.entries) && self.len == other.len
+    }
+}
+
+impl OutputLog {
+    pub fn extend(&mut self, entries: Vec<OutputEntry>) {
+        let mut all = self.entries.borrow_mut();
+        all.extend(entries);
+        self.len = all.len();
+    }
+
+    pub fn entries(&self) -> Ref<'_, [OutputEntry]> {
+        Ref::map(self.entries.borrow(), |entries| &entries[..self.len])
+    }
+}
+
+/// Pending hand-over of buffered values, cancelled when dropped.
+#[derive(Debug)]
+enum Flush {
+    Frame(AnimationFrame),
+    /// Animation frames don't fire while the tab is hidden.
+    Timeout(Timeout),
+}
+
+/// Background tabs get a timer instead, with a frame's worth of delay.
+const HIDDEN_FLUSH_DELAY_MS: u32 = 16;
+
+/// Buffers written values and hands them over once per animation frame, so a
+/// program printing thousands of values doesn't re-render the console for each.
+#[derive(Debug, Default, Clone)]
 pub struct CustomWriter {
-    pub on_write: Callback<String>,
+    pub on_write: Callback<Vec<OutputEntry>>,
+    origin: Rc<Cell<WriteOrigin>>,
+    buffer: Rc<RefCell<Vec<OutputEntry>>>,
+    flush_scheduled: Rc<Cell<bool>>,
+    // Never cleared from its own callback: dropping it there would free the
+    // closure that is running.
+    flush: Rc<RefCell<Option<Flush>>>,
+}
+
+impl PartialEq for CustomWriter {
+    fn eq(&self, other: &Self) -> bool {
+        self.on_write == other.on_write && Rc::ptr_eq(&self.buffer, &other.buffer)
+    }
 }
 
 impl CustomWriter {
-    pub fn new(on_write: Callback<String>) -> Self {
-        Self { on_write }
+    pub fn new(on_write: Callback<Vec<OutputEntry>>) -> Self {
+        Self {
+            on_write,
+            ..Default::default()
+        }
+    }
+
+    /// Tags everything written from now on. Shared between clones, so it can be
+    /// updated after the writer was handed over to the `Ram`.
+    pub fn set_origin(&self, origin: WriteOrigin) {
+        self.origin.set(origin);
+    }
+
+    /// Drops values of a previous run that were not handed over yet.
+    pub fn discard(&self) {
+        self.buffer.borrow_mut().clear();
+        self.flush.replace(None);
+        self.flush_scheduled.set(false);
+    }
+
+    fn schedule_flush(&self) {
+        if self.flush_scheduled.replace(true) {
+            return;
+        }
+        let (buffer, scheduled) = (self.buffer.clone(), self.flush_scheduled.clone());
+        let on_write = self.on_write.clone();
+        let flush = move || {
+            scheduled.set(false);
+            let entries = std::mem::take(&mut *buffer.borrow_mut());
+            if !entries.is_empty() {
+                on_write.emit(entries);
+            }
+        };
+        let flush = if gloo::utils::document().hidden() {
+            Flush::Timeout(Timeout::new(HIDDEN_FLUSH_DELAY_MS, flush))
+        } else {
+            Flush::Frame(request_animation_frame(move |_| flush()))
+        };
+        self.flush.replace(Some(flush));
     }
 }
 
@@ -17,7 +126,11 @@ impl Write for CustomWriter {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         let data = String::from_utf8_lossy(buf).to_string();
         log::debug!("Writing to custom writer: {:?}", &data);
-        self.on_write.emit(data);
+        self.buffer.borrow_mut().push(OutputEntry {
+            value: data,
+            origin: self.origin.get(),
+        });
+        self.schedule_flush();
         Ok(buf.len())
     }
 
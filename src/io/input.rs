@@ -1,34 +1,264 @@
 #![allow(non_camel_case_types)]
 
-use web_sys::HtmlInputElement;
+use std::{cmp::Ordering, ops::Range};
+
+use gloo::file::{
+    callbacks::{read_as_text, FileReader},
+    File,
+};
+use web_sys::{Element, HtmlInputElement, HtmlTextAreaElement};
 use yew::prelude::*;
 use yewdux::prelude::*;
 
-use crate::store::{dispatch, Store};
+use crate::{
+    io::{custom_reader::CustomReader, io_mode::IoMode, presets::PresetsComponent},
+    store::{dispatch, run_dispatch, RunStore, Store},
+};
+
+const TOKENS_BEFORE: usize = 32;
+const TOKENS_AFTER: usize = 512;
 
 #[function_component(InputComponent)]
 pub fn input_component() -> Html {
     let value = use_selector(|s: &Store| s.stdin.clone());
+    let position = *use_selector(|s: &RunStore| s.stdin_position);
+    let read_only = *use_selector(|s: &Store| s.read_only);
+    let allow_invalid = *use_selector(|s: &Store| s.allow_invalid_stdin);
+    let mode = *use_selector(|s: &Store| s.io_mode);
+    let backdrop_ref = use_node_ref();
+    let file_reader = use_state(|| None::<FileReader>);
+    let dragging = use_state_eq(|| false);
+    let preview = use_state_eq(|| false);
 
     let handle_change = |event: InputEvent| {
-        let Some(input) = event.target_dyn_into::<HtmlInputElement>() else {
+        let Some(input) = event.target_dyn_into::<HtmlTextAreaElement>() else {
             log::error!("Failed to cast event target to HtmlTextAreaElement");
             return;
         };
         let value = input.value();
-        dispatch().reduce_mut(|s| s.stdin = value);
+        dispatch().reduce_mut(|s| s.set_stdin(value));
+        run_dispatch().reduce_mut(|s| s.stdin_position = None);
+    };
+
+    let handle_scroll = {
+        let backdrop_ref = backdrop_ref.clone();
+        move |event: Event| {
+            let textarea = event.target_dyn_into::<HtmlTextAreaElement>();
+            if let Some((textarea, backdrop)) = textarea.zip(backdrop_ref.cast::<Element>()) {
+                backdrop.set_scroll_top(textarea.scroll_top());
+            }
+        }
+    };
+
+    // The reader is kept in state because dropping it cancels the read.
+    let load_file = Callback::from({
+        let file_reader = file_reader.clone();
+        move |file: web_sys::File| {
+            let file = File::from(file);
+            let reader = read_as_text(&file, |text| match text {
+                Ok(text) => {
+                    dispatch().reduce_mut(|s| s.set_stdin(text));
+                    run_dispatch().reduce_mut(|s| s.stdin_position = None);
+                }
+                Err(err) => log::error!("Failed to read input file: {err}"),
+            });
+            file_reader.set(Some(reader));
+        }
+    });
+
+    let handle_file_change = {
+        let load_file = load_file.clone();
+        move |event: Event| {
+            let Some(input) = event.target_dyn_into::<HtmlInputElement>() else {
+                log::error!("Failed to cast event target to HtmlInputElement");
+                return;
+            };
+            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                load_file.emit(file);
+            }
+            // Allows loading the same file again after editing the input
+            input.set_value("");
+        }
+    };
+
+    let handle_drag_over = {
+        let dragging = dragging.clone();
+        move |event: DragEvent| {
+            if !read_only {
+                event.prevent_default();
+                dragging.set(true);
+            }
+        }
+    };
+
+    let handle_drag_leave = {
+        let dragging = dragging.clone();
+        move |_: DragEvent| dragging.set(false)
+    };
+
+    let handle_drop = {
+        let dragging = dragging.clone();
+        move |event: DragEvent| {
+            event.prevent_default();
+            dragging.set(false);
+            if read_only {
+                return;
+            }
+            let files = event.data_transfer().and_then(|data| data.files());
+            if let Some(file) = files.and_then(|files| files.get(0)) {
+                load_file.emit(file);
+            }
+        }
+    };
+
+    let toggle_allow_invalid =
+        |_: MouseEvent| dispatch().reduce_mut(|s| s.allow_invalid_stdin = !s.allow_invalid_stdin);
+
+    let toggle_preview = {
+        let preview = preview.clone();
+        move |_: MouseEvent| preview.set(!*preview)
+    };
+
+    let toggle_mode = |_: MouseEvent| {
+        dispatch().reduce_mut(|s| {
+            s.io_mode = match s.io_mode {
+                IoMode::Numbers => IoMode::Characters,
+                IoMode::Characters => IoMode::Numbers,
+            };
+        });
+    };
+
+    let values = use_memo((value.clone(), mode), |(value, mode)| {
+        CustomReader::values(value, *mode)
+    });
+    let invalid = use_memo((value.clone(), mode), |(value, mode)| {
+        invalid_ranges(value, *mode)
+    });
+    let invalid_count = invalid.len();
+    let status = match position {
+        Some(position) => {
+            let remaining = values.len().saturating_sub(position);
+            Some(format!("{position} read, {remaining} left"))
+        }
+        None => preview.then(|| format!("{} values", values.len())),
+    };
+
+    let input = match position.filter(|_| read_only) {
+        Some(position) => token_view(&values, Some(position), mode),
+        None if *preview => token_view(&values, None, mode),
+        None => html! {
+          <div class="input-editor">
+            <div class="input-values input-backdrop" ref={backdrop_ref}>
+              {highlight_invalid(&value, &invalid)}
+            </div>
+            <textarea
+              class="input-values"
+              placeholder="Enter input, e.g. 1 2 3, 5x3, 1..10, rand(1,100)*20, #n"
+              spellcheck="false"
+              oninput={handle_change}
+              onscroll={handle_scroll}
+              value={value.to_string()}
+            />
+          </div>
+        },
     };
 
     html! {
-      <div class="console-input">
+      <div
+        class={classes!("console-input", dragging.then_some("dragging"))}
+        ondragover={handle_drag_over}
+        ondragleave={handle_drag_leave}
+        ondrop={handle_drop}
+      >
         <div class="input-marker">{">>>"}</div>
-        <input
-          type="text"
-          class="input-values"
-          placeholder="Enter input"
-          oninput={handle_change}
-          value={value.to_string()}
-        />
+        {input}
+        if !read_only {
+          <div class="input-tools">
+            <label class="input-status input-file" title="Load input from a file">
+              {"Load file"}
+              <input type="file" accept=".txt,.in,text/plain" onchange={handle_file_change} />
+            </label>
+            <div
+              class={classes!("input-status", "input-file", preview.then_some("active"))}
+              title="Show the values the input expands to"
+              onclick={toggle_preview}
+            >
+              {"Preview"}
+            </div>
+            <div
+              class={classes!("input-status", "input-file", (mode == IoMode::Characters).then_some("active"))}
+              title="Read input text as character codes and show output as characters"
+              onclick={toggle_mode}
+            >
+              {"Characters"}
+            </div>
+            <PresetsComponent />
+          </div>
+        }
+        if let Some(status) = status {
+          <div class="input-status">{status}</div>
+        }
+        if invalid_count > 0 && !read_only {
+          <div class="input-status input-invalid">
+            {format!("{invalid_count} invalid")}
+            <label>
+              <input type="checkbox" checked={allow_invalid} onclick={toggle_allow_invalid} />
+              {"run anyway"}
+            </label>
+          </div>
+        }
       </div>
     }
 }
+
+/// Renders expanded values, greying out the ones before the read `position`.
+fn token_view(values: &[String], position: Option<usize>, mode: IoMode) -> Html {
+    // Huge inputs would render tens of thousands of spans, so only
+    // the values around the read position are shown.
+    let current = position.unwrap_or_default();
+    let last = current.saturating_add(TOKENS_AFTER).min(values.len());
+    let first = current.saturating_sub(TOKENS_BEFORE).min(last);
+    let tokens = values[first..last].iter().zip(first..).map(|(value, i)| {
+        let class = match position.map(|position| i.cmp(&position)) {
+            Some(Ordering::Less) => "input-consumed",
+            Some(Ordering::Equal) => "input-next",
+            Some(Ordering::Greater) | None => "input-pending",
+        };
+        let title = (mode == IoMode::Characters).then(|| value.clone());
+        html! { <span class={class} {title}>{mode.display_value(value)}</span> }
+    });
+    let hidden = |count: usize| {
+        (count > 0).then(|| html! { <span class="input-hidden">{format!("…{count}")}</span> })
+    };
+    html! {
+      <div class="input-values input-tokens">
+        {hidden(first)}
+        { for tokens }
+        {hidden(values.len() - last)}
+      </div>
+    }
+}
+
+/// Byte ranges of the values in `input` that `READ` can't parse.
+fn invalid_ranges(input: &str, mode: IoMode) -> Vec<Range<usize>> {
+    CustomReader::invalid_values(input, mode)
+        .map(|value| {
+            let start = value.as_ptr() as usize - input.as_ptr() as usize;
+            start..start + value.len()
+        })
+        .collect()
+}
+
+/// Mirrors `input` with the `invalid` ranges marked, to be drawn behind the textarea.
+fn highlight_invalid(input: &str, invalid: &[Range<usize>]) -> Html {
+    let mut parts = Vec::new();
+    let mut last = 0;
+    for range in invalid {
+        parts.push(html! { {&input[last..range.start]} });
+        parts.push(html! { <mark class="input-invalid-value">{&input[range.clone()]}</mark> });
+        last = range.end;
+    }
+    // A trailing newline would otherwise collapse and misalign the last line.
+    parts.push(html! { {format!("{} ", &input[last..])} });
+    parts.into_iter().collect()
+}
@@ -0,0 +1,236 @@
+//! Small expression language for generating stdin.
+//!
+//! Input is a whitespace-separated list of terms, where whitespace inside
+//! parentheses does not separate:
+//!
+//! - `42` - a single value
+//! - `5x3` - a value repeated (`5 5 5`)
+//! - `1..5` / `5..1` - an inclusive ascending or descending range
+//! - `rand(1,100)` / `rand(1,100,7)` - a random value in `1..=100`, optionally
+//!   with its own seed; `rand(1,100)*20` yields twenty of them
+//! - `#n` - the number of values following it, up to the next `#n`
+//!
+//! Anything else is passed through unchanged so `READ` reports it as before.
+
+/// Seed for `rand` terms without an explicit one, so the expansion is reproducible.
+const DEFAULT_SEED: u64 = 0x5EED;
+/// Guards against typos like `1..1000000000` freezing the page.
+const MAX_VALUES: usize = 1_000_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Term {
+    Repeat { value: Atom, times: usize },
+    Range { from: i64, to: i64 },
+    Count,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Atom {
+    Value(i64),
+    Rand {
+        low: i64,
+        high: i64,
+        seed: Option<u64>,
+    },
+}
+
+fn parse_term(token: &str) -> Option<Term> {
+    if token == "#n" {
+        return Some(Term::Count);
+    }
+    if let Some((from, to)) = token.split_once("..") {
+        let (from, to) = (from.parse().ok()?, to.parse().ok()?);
+        return Some(Term::Range { from, to });
+    }
+    let (atom, times) = match token.rsplit_once(['x', '*']) {
+        Some((atom, times)) => (atom, times.parse().ok()?),
+        None => (token, 1),
+    };
+    let value = parse_atom(atom)?;
+    Some(Term::Repeat { value, times })
+}
+
+fn parse_atom(atom: &str) -> Option<Atom> {
+    let Some(args) = atom.strip_prefix("rand(").and_then(|a| a.strip_suffix(')')) else {
+        return atom.parse().ok().map(Atom::Value);
+    };
+    let mut args = args.split(',').map(str::trim);
+    let low = args.next()?.parse().ok()?;
+    let high = args.next()?.parse().ok()?;
+    let seed = match args.next() {
+        Some(seed) => Some(seed.parse().ok()?),
+        None => None,
+    };
+    if args.next().is_some() || low > high {
+        return None;
+    }
+    Some(Atom::Rand { low, high, seed })
+}
+
+/// `SplitMix64`, good enough for test data and tiny in wasm.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn between(&mut self, low: i64, high: i64) -> i64 {
+        let span = high.abs_diff(low).wrapping_add(1);
+        let offset = if span == 0 {
+            self.next()
+        } else {
+            self.next() % span
+        };
+        low.wrapping_add(offset as i64)
+    }
+}
+
+/// Splits `input` into terms at whitespace, except inside parentheses, so
+/// `rand(1, 100)` stays one term. A line break always ends a term.
+pub fn tokens(input: &str) -> impl Iterator<Item = &str> {
+    input.lines().flat_map(|line| {
+        let mut depth = 0_usize;
+        line.split(move |c: char| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            c.is_whitespace() && depth == 0
+        })
+        .filter(|token| !token.is_empty())
+    })
+}
+
+/// Whether `token` is a value or a term of the input language.
+pub fn is_valid(token: &str) -> bool {
+    token.parse::<i64>().is_ok() || parse_term(token).is_some()
+}
+
+/// Expands `input` into the values `READ` will consume, in order.
+pub fn expand(input: &str) -> Vec<String> {
+    let mut random = Random(DEFAULT_SEED);
+    let mut values = Vec::new();
+    let mut count_at = None;
+
+    for token in tokens(input) {
+        let room = MAX_VALUES.saturating_sub(values.len());
+        if token.parse::<i64>().is_ok() {
+            values.push(token.to_owned());
+            continue;
+        }
+        match parse_term(token) {
+            Some(Term::Count) => {
+                close_count(&mut values, count_at);
+                count_at = Some(values.len());
+                values.push(String::new());
+            }
+            Some(Term::Range { from, to }) if from <= to => {
+                values.extend((from..=to).take(room).map(|v| v.to_string()));
+            }
+            Some(Term::Range { from, to }) => {
+                values.extend((to..=from).rev().take(room).map(|v| v.to_string()));
+            }
+            Some(Term::Repeat { value, times }) => {
+                let mut seeded = None;
+                for _ in 0..times.min(room) {
+                    let value = match value {
+                        Atom::Value(value) => value,
+                        Atom::Rand { low, high, seed } => match seed {
+                            Some(seed) => seeded.get_or_insert(Random(seed)).between(low, high),
+                            None => random.between(low, high),
+                        },
+                    };
+                    values.push(value.to_string());
+                }
+            }
+            None => values.push(token.to_owned()),
+        }
+    }
+    close_count(&mut values, count_at);
+
+    values
+}
+
+/// Replaces the `#n` placeholder at `count_at` with the number of values after it.
+fn close_count(values: &mut [String], count_at: Option<usize>) {
+    if let Some(index) = count_at {
+        values[index] = (values.len() - index - 1).to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_pass_through() {
+        assert_eq!(expand("1 -2\n3"), ["1", "-2", "3"]);
+    }
+
+    #[test]
+    fn repeats() {
+        assert_eq!(expand("5x3 7*2"), ["5", "5", "5", "7", "7"]);
+        assert_eq!(expand("5x0"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn ranges_go_both_ways() {
+        assert_eq!(expand("1..4"), ["1", "2", "3", "4"]);
+        assert_eq!(expand("3..1"), ["3", "2", "1"]);
+        assert_eq!(expand("-1..-1"), ["-1"]);
+    }
+
+    #[test]
+    fn count_covers_values_up_to_the_next_one() {
+        assert_eq!(expand("#n 1..3 #n 9"), ["3", "1", "2", "3", "1", "9"]);
+        assert_eq!(expand("#n"), ["0"]);
+    }
+
+    #[test]
+    fn rand_stays_in_range_and_is_reproducible() {
+        let values = expand("rand(1,6)*100");
+        assert_eq!(values.len(), 100, "rand(1,6)*100 should yield 100 values");
+        assert!(
+            values
+                .iter()
+                .all(|v| (1..=6).contains(&v.parse::<i64>().unwrap())),
+            "{values:?} should all be dice rolls"
+        );
+        assert_eq!(values, expand("rand(1,6)*100"));
+        assert_eq!(expand("rand(1,6,7)*5"), expand("rand(1,6,7)*5"));
+        assert_eq!(expand("rand(4,4)x2"), ["4", "4"]);
+    }
+
+    #[test]
+    fn invalid_terms_pass_through() {
+        assert_eq!(expand("abc 1..x rand(5,1)"), ["abc", "1..x", "rand(5,1)"]);
+        assert!(!is_valid("abc"), "abc is not a term");
+        assert!(!is_valid("rand(5,1)"), "rand needs low <= high");
+    }
+
+    #[test]
+    fn rand_arguments_may_be_spaced() {
+        assert_eq!(
+            tokens("rand(1, 100)*20 7\n( 1\n2").collect::<Vec<_>>(),
+            ["rand(1, 100)*20", "7", "( 1", "2"]
+        );
+        let values = expand("rand(1, 6, 3)x2 7");
+        assert_eq!(values, expand("rand(1,6,3)x2 7"));
+        assert_eq!(values.len(), 3);
+        assert!(values.iter().all(|value| is_valid(value)));
+    }
+
+    #[test]
+    fn generated_values_are_capped() {
+        assert_eq!(expand("1..2000000").len(), MAX_VALUES);
+        assert_eq!(expand("0x2000000").len(), MAX_VALUES);
+        // Values typed out are never dropped
+        assert_eq!(expand("0x2000000 1").len(), MAX_VALUES + 1);
+    }
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// How the console reads stdin and shows written values.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum IoMode {
+    #[default]
+    Numbers,
+    /// Stdin text is read as a sequence of code points, and written values are
+    /// shown as the characters they encode.
+    Characters,
+}
+
+impl IoMode {
+    /// Formats a written value for exports.
+    pub fn format_value(self, value: &str) -> String {
+        match (self, Self::char(value)) {
+            (Self::Characters, Some(char)) => char.to_string(),
+            _ => value.to_owned(),
+        }
+    }
+
+    /// Formats a value for the console, where whitespace characters would
+    /// otherwise be invisible.
+    pub fn display_value(self, value: &str) -> String {
+        match (self, Self::char(value)) {
+            (Self::Characters, Some('\n')) => "\\n".to_owned(),
+            (Self::Characters, Some('\r')) => "\\r".to_owned(),
+            (Self::Characters, Some('\t')) => "\\t".to_owned(),
+            (Self::Characters, Some(' ')) => "␠".to_owned(),
+            _ => self.format_value(value),
+        }
+    }
+
+    fn char(value: &str) -> Option<char> {
+        value.trim().parse().ok().and_then(char::from_u32)
+    }
+}
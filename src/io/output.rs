@@ -1,33 +1,224 @@
+use std::fmt::{self, Display};
+
 use ramemu::errors::{InterpretError, ParseError};
+use web_sys::HtmlSelectElement;
 use yew::prelude::*;
+use yewdux::use_selector;
+
+use crate::{
+    io::{
+        custom_writer::{OutputEntry, OutputLog},
+        io_mode::IoMode,
+    },
+    store::{dispatch, Store},
+};
+#[cfg(not(feature = "ssr"))]
+use crate::utils::{copy_to_clipboard, download_file, jump_to_line};
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum OutputComponentErrors {
     InterpretError(InterpretError),
     ParseError(ParseError),
+    /// Stdin values that are not integers, reported before the run starts.
+    InvalidInput(Vec<String>),
+}
+
+impl Display for OutputComponentErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InterpretError(err) => write!(f, "{err}"),
+            Self::ParseError(err) => write!(f, "{err}"),
+            Self::InvalidInput(values) => {
+                let values = values.iter().map(|v| format!("`{v}`")).collect::<Vec<_>>();
+                write!(
+                    f,
+                    "Input values are not integers: {}. Fix them or allow invalid input to run anyway.",
+                    values.join(", ")
+                )
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Export {
+    Output,
+    OutputWithErrors,
+    Report,
+}
+
+impl Export {
+    const ALL: [Self; 3] = [Self::Output, Self::OutputWithErrors, Self::Report];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Output => "Output",
+            Self::OutputWithErrors => "Output + errors",
+            Self::Report => "Run report",
+        }
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Output | Self::OutputWithErrors => "output.txt",
+            Self::Report => "report.txt",
+        }
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    fn text(
+        self,
+        errors: &[OutputComponentErrors],
+        output: &[OutputEntry],
+        mode: IoMode,
+    ) -> String {
+        use crate::store::run_dispatch;
+        use ramemu::registers::RegisterId;
+
+        let separator = match mode {
+            IoMode::Numbers => "\n",
+            IoMode::Characters => "",
+        };
+        let output_lines = output
+            .iter()
+            .map(|entry| mode.format_value(&entry.value) + separator);
+        let error_lines = errors.iter().map(|err| format!("{err}\n"));
+
+        match self {
+            Self::Output => output_lines.collect(),
+            Self::OutputWithErrors => output_lines.chain(error_lines).collect(),
+            Self::Report => {
+                let store = dispatch().get();
+                let code = store.get_model().get_value();
+                let run = run_dispatch().get();
+                let registers = &run.registers;
+                let registers = (0..REPORT_REGISTERS)
+                    .map(|index| (index, registers.get(RegisterId(index))))
+                    .filter(|&(index, value)| index == 0 || value != 0)
+                    .map(|(index, value)| format!("R{index} = {value}\n"));
+
+                let mut report = format!("# Code\n{}\n\n", code.trim_end());
+                report += &format!("# Input\n{}\n\n", store.stdin.trim_end());
+                report += "# Output\n";
+                report.extend(output_lines);
+                if !errors.is_empty() {
+                    report += "\n# Errors\n";
+                    report.extend(error_lines);
+                }
+                report += "\n# Registers\n";
+                report.extend(registers);
+                report
+            }
+        }
+    }
 }
 
+/// Registers listed in run reports, where those holding zero are left out.
+#[cfg(not(feature = "ssr"))]
+const REPORT_REGISTERS: usize = 1024;
+
 #[derive(Clone, PartialEq, Properties)]
 pub struct Props {
     pub errors: Vec<OutputComponentErrors>,
-    pub output: AttrValue,
+    pub output: OutputLog,
 }
 
+/// Rendering every entry of a huge output makes each update slow, so only the
+/// tail is shown until asked otherwise. Exports always contain everything.
+const RENDER_LIMIT: usize = 1000;
+
 #[function_component(OutputComponent)]
 pub fn output_component(props: &Props) -> Html {
-    let errors = props.errors.iter().map(|err| match err {
-        // TODO:
-        OutputComponentErrors::InterpretError(err) => {
-            html! { <div class="console-runtime-error-fg console-bold">{format!("{err}")}</div> }
+    let mode = *use_selector(|s: &Store| s.io_mode);
+    let export = use_state_eq(|| Export::Output);
+    let show_all = use_state_eq(|| false);
+
+    let errors = props.errors.iter().map(|err| {
+        let class = match err {
+            OutputComponentErrors::InterpretError(_) => "console-runtime-error-fg",
+            OutputComponentErrors::ParseError(_) | OutputComponentErrors::InvalidInput(_) => {
+                "console-parse-error-fg"
+            }
+        };
+        html! { <div class={classes!(class, "console-bold")}>{err.to_string()}</div> }
+    });
+    let output = props.output.entries();
+    let hidden = if *show_all {
+        0
+    } else {
+        output.len().saturating_sub(RENDER_LIMIT)
+    };
+    let entries = output[hidden..].iter().map(|entry| {
+        let origin = entry.origin;
+        let title = format!(
+            "step {}, line {}: click to see the registers at this step",
+            origin.step, origin.line
+        );
+        let onclick = move |_: MouseEvent| {
+            dispatch().reduce_mut(|s: &mut Store| s.inspected_step = Some(origin.step));
+            #[cfg(not(feature = "ssr"))]
+            jump_to_line(origin.line);
+        };
+        let value = mode.display_value(&entry.value);
+        html! { <div class="console-entry" {title} {onclick}>{value}</div> }
+    });
+
+    let handle_export_change = {
+        let export = export.clone();
+        move |event: Event| {
+            let Some(select) = event.target_dyn_into::<HtmlSelectElement>() else {
+                log::error!("Failed to cast event target to HtmlSelectElement");
+                return;
+            };
+            let index = select.selected_index().max(0) as usize;
+            export.set(Export::ALL[index.min(Export::ALL.len() - 1)]);
+        }
+    };
+
+    let on_copy = {
+        #[cfg(not(feature = "ssr"))]
+        let (export, errors, output) = (*export, props.errors.clone(), props.output.clone());
+        move |_: MouseEvent| {
+            #[cfg(not(feature = "ssr"))]
+            copy_to_clipboard(&export.text(&errors, &output.entries(), mode));
         }
-        OutputComponentErrors::ParseError(err) => {
-            html! { <div class="console-parse-error-fg console-bold">{format!("{err}")}</div> }
+    };
+
+    let on_download = {
+        #[cfg(not(feature = "ssr"))]
+        let (export, errors, output) = (*export, props.errors.clone(), props.output.clone());
+        move |_: MouseEvent| {
+            #[cfg(not(feature = "ssr"))]
+            {
+                let text = export.text(&errors, &output.entries(), mode);
+                if let Err(err) = download_file(&text, export.file_name()) {
+                    gloo::console::error!("Failed to download output: ", err);
+                }
+            }
         }
+    };
+
+    let options = Export::ALL.iter().map(|&kind| {
+        html! { <option selected={kind == *export}>{kind.label()}</option> }
     });
+
     html! {
-      <div class="console-output">
+      <div class={classes!("console-output", (mode == IoMode::Characters).then_some("console-chars"))}>
+        <div class="console-export">
+          <select onchange={handle_export_change} title="What to export">
+            { for options }
+          </select>
+          <button onclick={on_copy} title="Copy to clipboard">{"Copy"}</button>
+          <button onclick={on_download} title="Download">{"Download"}</button>
+        </div>
         { for errors }
-        <span style="white-space:pre"> { &props.output } </span>
+        if hidden > 0 {
+          <button class="console-show-all" onclick={move |_| show_all.set(true)}>
+            {format!("{hidden} earlier values hidden, show all")}
+          </button>
+        }
+        { for entries }
       </div>
     }
 }
@@ -0,0 +1,59 @@
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+use crate::store::{dispatch, run_dispatch, Store};
+
+#[function_component(PresetsComponent)]
+pub fn presets_component() -> Html {
+    let names = use_selector(|s: &Store| s.stdin_presets.keys().cloned().collect::<Vec<_>>());
+    let current = use_selector(|s: &Store| s.stdin_preset.clone());
+
+    let handle_select = |event: Event| {
+        let Some(select) = event.target_dyn_into::<HtmlSelectElement>() else {
+            log::error!("Failed to cast event target to HtmlSelectElement");
+            return;
+        };
+        let name = select.value();
+        dispatch().reduce_mut(|s| s.load_stdin_preset(&name));
+        run_dispatch().reduce_mut(|s| s.stdin_position = None);
+    };
+
+    let handle_save = {
+        let current = current.clone();
+        move |_: MouseEvent| {
+            let default = current.as_deref().unwrap_or_default();
+            let Some(name) = gloo::dialogs::prompt("Save input as:", Some(default)) else {
+                return;
+            };
+            let name = name.trim().to_owned();
+            if !name.is_empty() {
+                dispatch().reduce_mut(|s| s.save_stdin_preset(name));
+            }
+        }
+    };
+
+    let handle_delete = |_: MouseEvent| dispatch().reduce_mut(|s| s.delete_stdin_preset());
+
+    let options = names.iter().map(|name| {
+        let selected = current.as_deref() == Some(name.as_str());
+        html! { <option value={name.clone()} {selected}>{name}</option> }
+    });
+
+    html! {
+      <div class="input-presets">
+        <select class="input-preset" onchange={handle_select} title="Stdin presets">
+          <option value="" selected={current.is_none()} disabled=true>{"Presets"}</option>
+          { for options }
+        </select>
+        <div class="input-status input-file" title="Save input as a preset" onclick={handle_save}>
+          {"Save"}
+        </div>
+        if current.is_some() {
+          <div class="input-status input-file" title="Delete preset" onclick={handle_delete}>
+            {"Delete"}
+          </div>
+        }
+      </div>
+    }
+}
@@ -0,0 +1,106 @@
+use monaco::sys::editor::ICodeEditor;
+use wasm_bindgen::{closure::Closure, JsCast};
+use yew::prelude::*;
+use yewdux::use_selector;
+
+use crate::{
+    language::labels::{jumps, Jump, JumpKind},
+    store::{dispatch, Store},
+};
+
+const WIDTH: f64 = 48.;
+const LANE_WIDTH: f64 = 6.;
+const HEAD_SIZE: f64 = 4.;
+
+/// Redraws the component whenever the editor scrolls, once there is one.
+#[hook]
+fn use_editor_scroll() {
+    let has_editor = *use_selector(|s: &Store| s.editor.with_editor(|_| ()).is_some());
+    let redraw = use_force_update();
+    use_effect_with(has_editor, move |_| {
+        let listener = Closure::<dyn Fn()>::new(move || redraw.force_update());
+        let subscription = dispatch().get().editor.with_editor(|editor| {
+            let editor: &ICodeEditor = editor.as_ref();
+            editor.on_did_scroll_change(listener.as_ref().unchecked_ref())
+        });
+        move || {
+            if let Some(subscription) = subscription {
+                subscription.dispose();
+            }
+            drop(listener);
+        }
+    });
+}
+
+/// Arrows beside the editor from each jump to its label, taking the innermost
+/// lane that is free so nested loops stay apart.
+#[function_component(JumpArrows)]
+pub fn jump_arrows() -> Html {
+    use_editor_scroll();
+    let version = *use_selector(|s: &Store| s.model_version());
+    let last_step = *use_selector(|s: &Store| s.last_step);
+    let jumps = use_memo(version, |_| {
+        let mut jumps = jumps(&dispatch().get().get_model().get_value());
+        jumps.sort_by_key(|jump| jump.from.abs_diff(jump.to));
+        jumps
+    });
+
+    let editor = dispatch().get().editor.clone();
+    let geometry = editor.with_editor(|editor| {
+        let editor: &ICodeEditor = editor.as_ref();
+        let editor = editor.clone();
+        let scroll_top = editor.get_scroll_top();
+        let line_height = editor.get_top_for_line_number(2.) - editor.get_top_for_line_number(1.);
+        move |line: usize| {
+            editor.get_top_for_line_number(line as f64) - scroll_top + line_height / 2.
+        }
+    });
+    let Some(center) = geometry else {
+        return html! { <svg class="jump-arrows" /> };
+    };
+
+    let span = |jump: &Jump| jump.from.min(jump.to)..=jump.from.max(jump.to);
+    let mut lanes: Vec<(usize, &Jump)> = Vec::new();
+    for jump in jumps.iter() {
+        let lane = (0..)
+            .find(|&lane| {
+                !lanes.iter().any(|&(other_lane, other)| {
+                    other_lane == lane
+                        && span(other).start() <= span(jump).end()
+                        && span(jump).start() <= span(other).end()
+                })
+            })
+            .unwrap_or_default();
+        lanes.push((lane, jump));
+    }
+
+    let arrows = lanes.into_iter().map(|(lane, jump)| {
+        let (from, to) = (center(jump.from), center(jump.to));
+        let x = (WIDTH - LANE_WIDTH * (lane as f64 + 1.)).max(1.);
+        let taken = last_step == Some((jump.from, jump.target));
+        let kind = match jump.kind {
+            JumpKind::Unconditional => "jump-unconditional",
+            JumpKind::Zero => "jump-zero",
+            JumpKind::Positive => "jump-positive",
+        };
+        let head = format!(
+            "{WIDTH},{to} {},{} {},{}",
+            WIDTH - HEAD_SIZE,
+            to - HEAD_SIZE,
+            WIDTH - HEAD_SIZE,
+            to + HEAD_SIZE
+        );
+        html! {
+          <g class={classes!("jump-arrow", kind, taken.then_some("taken"))}>
+            <path d={format!("M {WIDTH} {from} H {x} V {to} H {}", WIDTH - HEAD_SIZE)} />
+            <polygon points={head} />
+          </g>
+        }
+    });
+
+    html! {
+      <svg class="jump-arrows" width={WIDTH.to_string()}>
+        { for arrows }
+      </svg>
+    }
+}
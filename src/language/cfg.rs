@@ -0,0 +1,508 @@
+//! Control flow of a program, per instruction and as basic blocks.
+
+use std::{collections::HashMap, fmt::Write, ops::Range};
+
+use crate::{
+    language::labels::JumpKind,
+    monaco_ram::{instruction, Flow, Instruction, Writes},
+    syntax::{self, Line, Operand, Token},
+};
+
+/// An instruction line with what the analysis needs to know about it.
+pub struct Step<'a> {
+    pub line: Line<'a>,
+    pub opcode: Token<'a>,
+    pub instruction: &'static Instruction,
+}
+
+impl Step<'_> {
+    /// Index of the step a jump continues at.
+    pub fn target(&self, labels: &HashMap<&str, usize>) -> Option<usize> {
+        match self.line.operand()? {
+            Operand::Label(label) => labels.get(label).copied(),
+            _ => None,
+        }
+    }
+
+    /// Registers whose value the instruction uses.
+    pub fn reads(&self) -> Vec<usize> {
+        let mut reads = Vec::new();
+        if self.instruction.reads_accumulator {
+            reads.push(0);
+        }
+        match self.line.operand() {
+            Some(Operand::Direct(register)) if self.instruction.reads_operand => {
+                reads.push(register);
+            }
+            Some(Operand::Indirect(pointer)) => reads.push(pointer),
+            _ => {}
+        }
+        reads
+    }
+
+    /// The register the instruction writes, if it is known statically.
+    pub fn writes(&self) -> Option<usize> {
+        match (self.instruction.writes, self.line.operand()) {
+            (Writes::Accumulator, _) => Some(0),
+            (Writes::Operand, Some(Operand::Direct(register))) => Some(register),
+            _ => None,
+        }
+    }
+}
+
+/// Straight-line run of steps that is only entered at its first one.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BasicBlock {
+    pub steps: Range<usize>,
+    /// Editor lines the block covers, including labels on lines of their own.
+    pub first_line: usize,
+    pub last_line: usize,
+    pub reachable: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    /// `None` when control falls through to the next block.
+    pub jump: Option<JumpKind>,
+}
+
+pub struct Program<'a> {
+    pub lines: Vec<Line<'a>>,
+    /// The lines with a known instruction, in order.
+    pub steps: Vec<Step<'a>>,
+    /// Index of the step each label belongs to.
+    pub labels: HashMap<&'a str, usize>,
+    pub successors: Vec<Vec<usize>>,
+}
+
+impl<'a> Program<'a> {
+    pub fn new(code: &'a str) -> Self {
+        let lines: Vec<_> = syntax::scan(code).collect();
+        let steps: Vec<_> = lines
+            .iter()
+            .filter_map(|line| {
+                let opcode = line.opcode?;
+                Some(Step {
+                    line: *line,
+                    opcode,
+                    instruction: instruction(opcode.text)?,
+                })
+            })
+            .collect();
+
+        // A label on a line of its own belongs to the next instruction
+        let mut labels = HashMap::new();
+        let mut next_step = 0;
+        for line in &lines {
+            if let Some(label) = line.label {
+                labels.entry(label.text).or_insert(next_step);
+            }
+            if steps
+                .get(next_step)
+                .is_some_and(|step| step.line.number == line.number)
+            {
+                next_step += 1;
+            }
+        }
+
+        let successors: Vec<Vec<usize>> = steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                let next = (index + 1 < steps.len()).then_some(index + 1);
+                // A label after the last instruction ends the program like `HALT`
+                let target = step.target(&labels).filter(|&target| target < steps.len());
+                match step.instruction.flow {
+                    Flow::Halt => Vec::new(),
+                    Flow::Jump(JumpKind::Unconditional) => target.into_iter().collect(),
+                    Flow::Jump(_) => next.into_iter().chain(target).collect(),
+                    Flow::Next => next.into_iter().collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            lines,
+            steps,
+            labels,
+            successors,
+        }
+    }
+
+    /// Whether each step can be reached from the first one.
+    pub fn reachable(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.steps.len()];
+        let mut stack = if self.steps.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+        while let Some(index) = stack.pop() {
+            if !std::mem::replace(&mut reachable[index], true) {
+                stack.extend(&self.successors[index]);
+            }
+        }
+        reachable
+    }
+
+    /// Splits the steps at labels and after jumps and `HALT`.
+    pub fn blocks(&self) -> Vec<BasicBlock> {
+        let mut leaders = vec![false; self.steps.len()];
+        for &leader in self.labels.values() {
+            if let Some(leader) = leaders.get_mut(leader) {
+                *leader = true;
+            }
+        }
+        if let Some(first) = leaders.first_mut() {
+            *first = true;
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            if step.instruction.flow != Flow::Next {
+                if let Some(next) = leaders.get_mut(index + 1) {
+                    *next = true;
+                }
+            }
+        }
+        let starts: Vec<_> = (0..self.steps.len()).filter(|&i| leaders[i]).collect();
+
+        let reachable = self.reachable();
+        starts
+            .iter()
+            .zip(starts.iter().skip(1).chain([&self.steps.len()]))
+            .map(|(&start, &end)| {
+                let step_line = self.steps[start].line.number;
+                let first_line = self
+                    .lines
+                    .iter()
+                    .filter_map(|line| {
+                        let label = line.label?;
+                        (self.labels.get(label.text) == Some(&start)).then_some(line.number)
+                    })
+                    .fold(step_line, usize::min);
+                BasicBlock {
+                    steps: start..end,
+                    first_line,
+                    last_line: self.steps[end - 1].line.number,
+                    reachable: reachable[start],
+                }
+            })
+            .collect()
+    }
+
+    /// Edges between `blocks`, leaving each block from its last step.
+    pub fn edges(&self, blocks: &[BasicBlock]) -> Vec<Edge> {
+        let block_of = |step: usize| blocks.iter().position(|block| block.steps.contains(&step));
+        let mut edges = Vec::new();
+        for (from, block) in blocks.iter().enumerate() {
+            let last = block.steps.end - 1;
+            let step = &self.steps[last];
+            let kind = match step.instruction.flow {
+                Flow::Jump(kind) => Some(kind),
+                Flow::Next | Flow::Halt => None,
+            };
+            let falls_through = !matches!(
+                step.instruction.flow,
+                Flow::Halt | Flow::Jump(JumpKind::Unconditional)
+            );
+            if falls_through {
+                if let Some(to) = block_of(last + 1) {
+                    edges.push(Edge {
+                        from,
+                        to,
+                        jump: None,
+                    });
+                }
+            }
+            if let Some(to) = kind
+                .and_then(|_| step.target(&self.labels))
+                .and_then(block_of)
+            {
+                edges.push(Edge {
+                    from,
+                    to,
+                    jump: kind,
+                });
+            }
+        }
+        edges
+    }
+
+    /// Source of each block without comments or blank lines.
+    fn block_text(&self, block: &BasicBlock) -> Vec<&'a str> {
+        self.lines[block.first_line - 1..block.last_line]
+            .iter()
+            .filter_map(|line| {
+                let code = line
+                    .comment
+                    .map_or(line.text, |comment| &line.text[..comment.start]);
+                Some(code.trim()).filter(|code| !code.is_empty())
+            })
+            .collect()
+    }
+
+    /// Graphviz rendering of the graph, for use in reports.
+    pub fn to_dot(&self) -> String {
+        let blocks = self.blocks();
+        let mut dot = String::from("digraph cfg {\n");
+        dot += "  node [shape=box, fontname=\"monospace\"];\n";
+        if !blocks.is_empty() {
+            dot += "  entry [shape=point];\n  entry -> b0;\n";
+        }
+        for (index, block) in blocks.iter().enumerate() {
+            let label: String = self
+                .block_text(block)
+                .into_iter()
+                .map(|text| text.replace('\\', "\\\\").replace('"', "\\\"") + "\\l")
+                .collect();
+            let style = if block.reachable {
+                ""
+            } else {
+                ", style=dashed"
+            };
+            let _ = writeln!(dot, "  b{index} [label=\"{label}\"{style}];");
+        }
+        for edge in self.edges(&blocks) {
+            let attributes = match edge.jump {
+                None => "",
+                Some(JumpKind::Unconditional) => " [label=\"jump\"]",
+                Some(JumpKind::Zero) => " [label=\"= 0\"]",
+                Some(JumpKind::Positive) => " [label=\"> 0\"]",
+            };
+            let _ = writeln!(dot, "  b{} -> b{}{attributes};", edge.from, edge.to);
+        }
+        dot += "}\n";
+        dot
+    }
+
+    /// Standalone SVG with the blocks stacked in program order, fallthrough
+    /// edges between neighbours and jumps routed in lanes on the right.
+    /// Each block carries its lines in `data-first-line` and `data-last-line`.
+    pub fn to_svg(&self, current_line: Option<usize>) -> String {
+        const CHAR_WIDTH: f64 = 7.2;
+        const LINE_HEIGHT: f64 = 16.;
+        const PADDING: f64 = 6.;
+        const GAP: f64 = 24.;
+        const MARGIN: f64 = 8.;
+        const LANE_WIDTH: f64 = 10.;
+        const HEAD_SIZE: f64 = 4.;
+
+        let blocks = self.blocks();
+        let edges = self.edges(&blocks);
+        let texts: Vec<_> = blocks.iter().map(|block| self.block_text(block)).collect();
+
+        let longest = texts
+            .iter()
+            .flatten()
+            .map(|text| text.chars().count())
+            .max();
+        let width = (longest.unwrap_or_default() as f64 * CHAR_WIDTH + 2. * PADDING).ceil();
+        let mut tops = Vec::with_capacity(blocks.len());
+        let mut y = MARGIN;
+        for text in &texts {
+            tops.push(y);
+            y += text.len() as f64 * LINE_HEIGHT + 2. * PADDING + GAP;
+        }
+        let height = |index: usize| texts[index].len() as f64 * LINE_HEIGHT + 2. * PADDING;
+        let bottom = |index: usize| tops[index] + height(index);
+        let right = MARGIN + width;
+
+        // Edges to anything but the next block go around the right side, in
+        // the innermost lane their vertical span doesn't share with another.
+        let mut lanes: Vec<(usize, f64, f64)> = Vec::new();
+        let mut routed: Vec<_> = edges
+            .iter()
+            .filter(|edge| edge.to != edge.from + 1)
+            .map(|edge| {
+                let from = bottom(edge.from) - PADDING;
+                let to = tops[edge.to] + PADDING;
+                (edge, from, to)
+            })
+            .collect();
+        routed.sort_by(|a, b| (a.1 - a.2).abs().total_cmp(&(b.1 - b.2).abs()));
+        let mut paths = String::new();
+        for (edge, from, to) in routed {
+            let (low, high) = (from.min(to), from.max(to));
+            let lane = (0..)
+                .find(|&lane| {
+                    !lanes
+                        .iter()
+                        .any(|&(other, start, end)| other == lane && start <= high && low <= end)
+                })
+                .unwrap_or_default();
+            lanes.push((lane, low, high));
+            let x = right + LANE_WIDTH * (lane as f64 + 1.);
+            let _ = write!(
+                paths,
+                r#"<g class="{}"><path d="M {right} {from} H {x} V {to} H {}"/><polygon points="{right},{to} {},{} {},{}"/></g>"#,
+                edge_class(edge),
+                right + HEAD_SIZE,
+                right + HEAD_SIZE,
+                to - HEAD_SIZE,
+                right + HEAD_SIZE,
+                to + HEAD_SIZE,
+            );
+        }
+        let center = MARGIN + width / 2.;
+        for edge in edges.iter().filter(|edge| edge.to == edge.from + 1) {
+            let (from, to) = (bottom(edge.from), tops[edge.to]);
+            let _ = write!(
+                paths,
+                r#"<g class="{}"><path d="M {center} {from} V {}"/><polygon points="{center},{to} {},{} {},{}"/></g>"#,
+                edge_class(edge),
+                to - HEAD_SIZE,
+                center - HEAD_SIZE,
+                to - HEAD_SIZE,
+                center + HEAD_SIZE,
+                to - HEAD_SIZE,
+            );
+        }
+
+        let mut nodes = String::new();
+        for (index, (block, text)) in blocks.iter().zip(&texts).enumerate() {
+            let current = current_line
+                .is_some_and(|line| (block.first_line..=block.last_line).contains(&line));
+            let mut class = String::from("cfg-block");
+            if !block.reachable {
+                class += " cfg-unreachable";
+            }
+            if current {
+                class += " cfg-current";
+            }
+            let _ = write!(
+                nodes,
+                r#"<g class="{class}" data-first-line="{}" data-last-line="{}"><rect x="{MARGIN}" y="{}" width="{width}" height="{}"/>"#,
+                block.first_line,
+                block.last_line,
+                tops[index],
+                height(index),
+            );
+            for (row, line) in text.iter().enumerate() {
+                let baseline = tops[index] + PADDING + LINE_HEIGHT * (row as f64 + 0.75);
+                let _ = write!(
+                    nodes,
+                    r#"<text x="{}" y="{baseline}">{}</text>"#,
+                    MARGIN + PADDING,
+                    escape_xml(line),
+                );
+            }
+            nodes += "</g>";
+        }
+
+        let lane_count = lanes.iter().map(|&(lane, ..)| lane + 1).max();
+        let total_width = right + LANE_WIDTH * (lane_count.unwrap_or_default() as f64 + 1.);
+        let total_height = (y - GAP + MARGIN).max(0.);
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{total_width}" height="{total_height}" viewBox="0 0 {total_width} {total_height}"><style>{SVG_STYLE}</style>{paths}{nodes}</svg>"#
+        )
+    }
+}
+
+/// Kept inside the SVG so exported files look the same as the panel.
+const SVG_STYLE: &str = "\
+.cfg-block rect { fill: #fff; stroke: #555; }\
+.cfg-block text { font: 12px monospace; fill: #222; white-space: pre; }\
+.cfg-unreachable rect { stroke-dasharray: 4 3; fill: #eee; }\
+.cfg-current rect { fill: #ffe9a8; stroke: #c08b00; stroke-width: 2; }\
+.cfg-edge path { fill: none; stroke: #777; }\
+.cfg-edge polygon { fill: #777; }\
+.cfg-zero path { stroke: #d19a66; } .cfg-zero polygon { fill: #d19a66; }\
+.cfg-positive path { stroke: #98c379; } .cfg-positive polygon { fill: #98c379; }\
+.cfg-unconditional path { stroke: #61afef; } .cfg-unconditional polygon { fill: #61afef; }";
+
+fn edge_class(edge: &Edge) -> &'static str {
+    match edge.jump {
+        None => "cfg-edge cfg-fallthrough",
+        Some(JumpKind::Unconditional) => "cfg-edge cfg-unconditional",
+        Some(JumpKind::Zero) => "cfg-edge cfg-zero",
+        Some(JumpKind::Positive) => "cfg-edge cfg-positive",
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailing_label_does_not_panic() {
+        let program = Program::new("read 1\nload 1\njzero end\nwrite 1\nend:\n");
+        let blocks = program.blocks();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(
+            program.edges(&blocks),
+            vec![Edge {
+                from: 0,
+                to: 1,
+                jump: None
+            }]
+        );
+        assert!(program.to_dot().contains("b0 -> b1;"));
+        assert!(program.to_svg(Some(4)).contains("cfg-current"));
+    }
+
+    #[test]
+    fn splits_blocks_at_labels_and_jumps() {
+        let code =
+            "# count down\nread 1\nloop: load 1\njzero end\nsub =1\nstore 1\njump loop\nend: halt";
+        let program = Program::new(code);
+        let blocks = program.blocks();
+        let spans: Vec<_> = blocks
+            .iter()
+            .map(|block| (block.steps.clone(), block.first_line, block.last_line))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![(0..1, 2, 2), (1..3, 3, 4), (3..6, 5, 7), (6..7, 8, 8)]
+        );
+        assert!(blocks.iter().all(|block| block.reachable));
+        assert_eq!(
+            program.edges(&blocks),
+            vec![
+                Edge {
+                    from: 0,
+                    to: 1,
+                    jump: None
+                },
+                Edge {
+                    from: 1,
+                    to: 2,
+                    jump: None
+                },
+                Edge {
+                    from: 1,
+                    to: 3,
+                    jump: Some(JumpKind::Zero)
+                },
+                Edge {
+                    from: 2,
+                    to: 1,
+                    jump: Some(JumpKind::Unconditional)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn code_after_halt_is_unreachable() {
+        let program = Program::new("halt\nwrite 1");
+        let blocks = program.blocks();
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks[0].reachable);
+        assert!(!blocks[1].reachable);
+        assert!(program.edges(&blocks).is_empty());
+    }
+
+    #[test]
+    fn empty_program_has_no_blocks() {
+        let program = Program::new("");
+        assert!(program.blocks().is_empty());
+    }
+}
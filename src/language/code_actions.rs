@@ -0,0 +1,256 @@
+//! Quick fixes for parse errors where the fix is obvious.
+
+use ramemu::{
+    errors::{InvalidArgument, ParseErrorKind},
+    parser::parse,
+};
+
+use crate::{
+    language::diagnostics::{diagnostics, is_defined, Diagnostic},
+    monaco_ram::{instruction, Instruction, INSTRUCTIONS},
+    syntax::{self, Line, Location, Operand, Token},
+};
+
+/// Misspellings further away than this from every instruction are left alone.
+const MAX_TYPO_DISTANCE: usize = 2;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TextEdit {
+    pub location: Location,
+    pub text: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CodeAction {
+    pub title: String,
+    pub edits: Vec<TextEdit>,
+    /// Applied by Monaco's "auto fix" without asking.
+    pub preferred: bool,
+}
+
+/// Fixes for the parse errors on lines `first_line..=last_line`.
+pub fn code_actions(code: &str, first_line: usize, last_line: usize) -> Vec<CodeAction> {
+    let lines: Vec<_> = syntax::scan(code).collect();
+    diagnostics(code)
+        .iter()
+        .filter(|diagnostic| (first_line..=last_line).contains(&diagnostic.location.line))
+        .flat_map(|diagnostic| fixes(&lines, diagnostic))
+        .collect()
+}
+
+fn fixes(lines: &[Line], diagnostic: &Diagnostic) -> Vec<CodeAction> {
+    let line = &lines[diagnostic.location.line - 1];
+    match &diagnostic.kind {
+        ParseErrorKind::UnsupportedOpcode(_) => match line.opcode {
+            Some(opcode) if instruction(opcode.text).is_some() => convert_dialect(line),
+            _ => fix_keyword(line),
+        },
+        ParseErrorKind::ArgumentIsNotValid(InvalidArgument::LabelIsNotValid) => {
+            match line.operand() {
+                Some(Operand::Label(label)) if !is_defined(lines, label) => {
+                    vec![create_label(lines, line, label)]
+                }
+                _ => Vec::new(),
+            }
+        }
+        ParseErrorKind::ArgumentIsNotValid(_) | ParseErrorKind::UnsupportedSyntax => {
+            remove_operand(line).into_iter().collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Whether `ramemu` knows `name`, in the dialect it was built with.
+fn is_supported(name: &str) -> bool {
+    let Err(errors) = parse(name) else {
+        return true;
+    };
+    !errors
+        .iter()
+        .any(|error| matches!(error.kind, ParseErrorKind::UnsupportedOpcode(_)))
+}
+
+fn replace(line: &Line, token: Token, text: String) -> TextEdit {
+    TextEdit {
+        location: Location::new(line, token),
+        text,
+    }
+}
+
+/// Spells `name` in the case `like` is written in.
+fn same_case(name: &str, like: &str) -> String {
+    if like.chars().any(char::is_uppercase) {
+        name.to_uppercase()
+    } else {
+        name.to_lowercase()
+    }
+}
+
+fn create_label(lines: &[Line], line: &Line, label: &str) -> CodeAction {
+    let halt = same_case("HALT", line.opcode.map_or("HALT", |opcode| opcode.text));
+    let edit = match lines.last() {
+        Some(last) => {
+            let end = syntax::column(last.text, last.text.len());
+            TextEdit {
+                location: Location {
+                    line: last.number,
+                    start_column: end,
+                    end_column: end,
+                },
+                text: format!("\n{label}: {halt}"),
+            }
+        }
+        None => TextEdit {
+            location: Location {
+                line: 1,
+                start_column: 1,
+                end_column: 1,
+            },
+            text: format!("{label}: {halt}"),
+        },
+    };
+    CodeAction {
+        title: format!("Create label `{label}` at the end of the program"),
+        edits: vec![edit],
+        preferred: false,
+    }
+}
+
+/// Nearest supported instructions to a misspelled one. Only a single match
+/// one edit away is safe to apply without asking.
+fn fix_keyword(line: &Line) -> Vec<CodeAction> {
+    let Some(opcode) = line.opcode else {
+        return Vec::new();
+    };
+    let typed = opcode.text.to_uppercase();
+    let mut candidates: Vec<_> = INSTRUCTIONS
+        .iter()
+        .flat_map(Instruction::names)
+        .filter(|name| is_supported(name))
+        .map(|name| (edit_distance(&typed, name), name))
+        .filter(|&(distance, _)| distance <= MAX_TYPO_DISTANCE)
+        .collect();
+    let Some(&(nearest, _)) = candidates.iter().min() else {
+        return Vec::new();
+    };
+    candidates.retain(|&(distance, _)| distance == nearest);
+    let preferred = nearest == 1 && candidates.len() == 1;
+    candidates
+        .into_iter()
+        .map(|(_, name)| {
+            let name = same_case(name, opcode.text);
+            CodeAction {
+                title: format!("Change to `{name}`"),
+                edits: vec![replace(line, opcode, name)],
+                preferred,
+            }
+        })
+        .collect()
+}
+
+/// Removes the operand of an instruction that takes none, like `HALT 1`.
+fn remove_operand(line: &Line) -> Option<CodeAction> {
+    let (opcode, code) = line.opcode.zip(line.code())?;
+    let takes_none = instruction(opcode.text).is_some_and(|i| i.modes.is_empty());
+    if !takes_none || line.operand.is_none() {
+        return None;
+    }
+    let operand = Token {
+        text: &line.text[opcode.end()..code.end()],
+        start: opcode.end(),
+    };
+    Some(CodeAction {
+        title: format!("Remove the operand of `{}`", opcode.text),
+        edits: vec![replace(line, operand, String::new())],
+        preferred: false,
+    })
+}
+
+/// Other spellings of an instruction `ramemu` doesn't know by this one.
+fn convert_dialect(line: &Line) -> Vec<CodeAction> {
+    let Some((opcode, instruction)) = line
+        .opcode
+        .and_then(|opcode| Some((opcode, instruction(opcode.text)?)))
+    else {
+        return Vec::new();
+    };
+    instruction
+        .names()
+        .filter(|name| !name.eq_ignore_ascii_case(opcode.text) && is_supported(name))
+        .map(|name| {
+            let name = same_case(name, opcode.text);
+            CodeAction {
+                title: format!("Convert to `{name}`"),
+                edits: vec![replace(line, opcode, name)],
+                preferred: false,
+            }
+        })
+        .collect()
+}
+
+/// Edit distance counting a swap of adjacent characters as one edit, the
+/// most common typo in `LAOD`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<_>, Vec<_>) = (a.chars().collect(), b.chars().collect());
+    let mut distance = vec![vec![0; b.len() + 1]; a.len() + 1];
+    distance[0] = (0..=b.len()).collect();
+    for (i, row) in distance.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = distance[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut best = substitution
+                .min(distance[i - 1][j] + 1)
+                .min(distance[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(distance[i - 2][j - 2] + 1);
+            }
+            distance[i][j] = best;
+        }
+    }
+    distance[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyword_fixes(text: &str) -> Vec<(String, bool)> {
+        let line = syntax::scan_line(1, text);
+        fix_keyword(&line)
+            .into_iter()
+            .map(|action| (action.edits[0].text.clone(), action.preferred))
+            .collect()
+    }
+
+    #[test]
+    fn single_typo_match_is_preferred() {
+        assert_eq!(keyword_fixes("LAOD 1"), [("LOAD".to_owned(), true)]);
+        assert_eq!(keyword_fixes("lod 1"), [("load".to_owned(), true)]);
+    }
+
+    #[test]
+    fn ambiguous_typo_is_not_preferred() {
+        assert_eq!(
+            keyword_fixes("MUB 1"),
+            [("SUB".to_owned(), false), ("MUL".to_owned(), false)]
+        );
+    }
+
+    #[test]
+    fn distant_typo_is_left_alone() {
+        assert_eq!(keyword_fixes("FOO 1"), []);
+    }
+
+    #[test]
+    fn edit_distance_counts_swaps_as_one() {
+        assert_eq!(edit_distance("LOAD", "LOAD"), 0);
+        assert_eq!(edit_distance("LAOD", "LOAD"), 1);
+        assert_eq!(edit_distance("LOD", "LOAD"), 1);
+        assert_eq!(edit_distance("LOADD", "LOAD"), 1);
+        assert_eq!(edit_distance("JZRO", "JZERO"), 1);
+        assert_eq!(edit_distance("", "HALT"), 4);
+        assert_eq!(edit_distance("ADD", "SUB"), 3);
+    }
+}
@@ -0,0 +1,223 @@
+//! Context-aware completion: instructions at the start of a line, labels after
+//! jumps, registers after `LOAD`/`STORE`, and snippets for common patterns.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    monaco_ram::{instruction, OperandMode, Writes, INSTRUCTIONS},
+    syntax::{self, Operand},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CompletionKind {
+    Keyword,
+    Reference,
+    Variable,
+    Snippet,
+}
+
+impl CompletionKind {
+    /// Name of the matching `monaco.languages.CompletionItemKind`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Keyword => "Keyword",
+            Self::Reference => "Reference",
+            Self::Variable => "Variable",
+            Self::Snippet => "Snippet",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+    pub insert_text: String,
+    pub detail: String,
+    /// Markdown.
+    pub documentation: String,
+    /// Whether `insert_text` contains snippet tab stops.
+    pub snippet: bool,
+}
+
+struct Snippet {
+    name: &'static str,
+    description: &'static str,
+    body: &'static str,
+}
+
+const SNIPPETS: &[Snippet] = &[
+    Snippet {
+        name: "loop",
+        description: "Counted loop",
+        body: "LOAD =${1:10}\n\
+               STORE ${2:1}\n\
+               ${3:loop}: ${4:WRITE ${2:1}}\n\
+               LOAD ${2:1}\n\
+               SUB =1\n\
+               STORE ${2:1}\n\
+               JGTZ ${3:loop}\n\
+               $0",
+    },
+    Snippet {
+        name: "copy",
+        description: "Array copy",
+        body: "LOAD =${1:10}\n\
+               STORE ${2:1} # source\n\
+               LOAD =${3:30}\n\
+               STORE ${4:2} # destination\n\
+               LOAD =${5:5}\n\
+               STORE ${6:3} # count\n\
+               ${7:copy}: LOAD *${2:1}\n\
+               STORE *${4:2}\n\
+               LOAD ${2:1}\n\
+               ADD =1\n\
+               STORE ${2:1}\n\
+               LOAD ${4:2}\n\
+               ADD =1\n\
+               STORE ${4:2}\n\
+               LOAD ${6:3}\n\
+               SUB =1\n\
+               STORE ${6:3}\n\
+               JGTZ ${7:copy}\n\
+               $0",
+    },
+    Snippet {
+        name: "readzero",
+        description: "Read until zero",
+        body: "${1:next}: READ ${2:1}\n\
+               LOAD ${2:1}\n\
+               JZERO ${3:done}\n\
+               ${4:WRITE ${2:1}}\n\
+               JUMP ${1:next}\n\
+               ${3:done}: HALT\n\
+               $0",
+    },
+];
+
+/// Suggestions for the cursor at the 1-based `line` and `column` of `code`.
+pub fn completions(code: &str, line: usize, column: usize) -> Vec<Completion> {
+    let text = line
+        .checked_sub(1)
+        .and_then(|index| code.lines().nth(index))
+        .unwrap_or_default();
+    let before = &text[..syntax::offset(text, column)];
+    if before.contains('#') {
+        return Vec::new();
+    }
+
+    let scanned = syntax::scan_line(line, before);
+    let typing = !before.is_empty() && !before.ends_with(char::is_whitespace);
+    // Keeps the old behaviour of following the case the user is typing in
+    let lowercase = typing && before.ends_with(char::is_lowercase);
+
+    match (scanned.opcode, scanned.operand) {
+        _ if scanned.rest.is_some() => Vec::new(),
+        (Some(_), None) if typing => keywords(lowercase).chain(snippets(lowercase)).collect(),
+        (None, _) => keywords(lowercase).chain(snippets(lowercase)).collect(),
+        (Some(opcode), None) => operands(code, opcode.text),
+        (Some(opcode), Some(_)) if typing => operands(code, opcode.text),
+        (Some(_), Some(_)) => Vec::new(),
+    }
+}
+
+fn operands(code: &str, opcode: &str) -> Vec<Completion> {
+    let Some(instruction) = instruction(opcode) else {
+        return Vec::new();
+    };
+    if instruction.modes.contains(&OperandMode::Label) {
+        labels(code)
+    } else if instruction.modes.contains(&OperandMode::Direct) {
+        registers(code)
+    } else {
+        Vec::new()
+    }
+}
+
+fn keywords(lowercase: bool) -> impl Iterator<Item = Completion> {
+    INSTRUCTIONS.iter().flat_map(move |instruction| {
+        instruction.names().map(move |name| {
+            let name = if lowercase {
+                name.to_lowercase()
+            } else {
+                name.to_owned()
+            };
+            Completion {
+                label: name.clone(),
+                kind: CompletionKind::Keyword,
+                insert_text: name,
+                detail: instruction.operand().to_owned(),
+                documentation: instruction.documentation(),
+                snippet: false,
+            }
+        })
+    })
+}
+
+fn snippets(lowercase: bool) -> impl Iterator<Item = Completion> {
+    SNIPPETS.iter().map(move |snippet| {
+        let body = if lowercase {
+            snippet.body.to_lowercase()
+        } else {
+            snippet.body.to_owned()
+        };
+        Completion {
+            label: snippet.name.to_owned(),
+            kind: CompletionKind::Snippet,
+            documentation: format!("```\n{}\n```", body.replace("$0", "")),
+            insert_text: body,
+            detail: snippet.description.to_owned(),
+            snippet: true,
+        }
+    })
+}
+
+fn labels(code: &str) -> Vec<Completion> {
+    syntax::scan(code)
+        .filter_map(|line| {
+            let label = line.label?;
+            Some(Completion {
+                label: label.text.to_owned(),
+                kind: CompletionKind::Reference,
+                insert_text: label.text.to_owned(),
+                detail: format!("line {}", line.number),
+                documentation: format!("```\n{}\n```", line.text.trim()),
+                snippet: false,
+            })
+        })
+        .collect()
+}
+
+/// Registers the program already uses, named after the comment on the first
+/// line that stores or reads into them, e.g. `STORE 3 # counter`.
+fn registers(code: &str) -> Vec<Completion> {
+    let mut aliases = BTreeMap::from([(0, Some("accumulator".to_owned()))]);
+    for line in syntax::scan(code) {
+        let Some(Operand::Direct(register) | Operand::Indirect(register)) = line.operand() else {
+            continue;
+        };
+        let alias = aliases.entry(register).or_default();
+        let writes = line
+            .opcode
+            .and_then(|opcode| instruction(opcode.text))
+            .map(|instruction| instruction.writes);
+        if alias.is_none() && writes == Some(Writes::Operand) {
+            *alias = line
+                .comment
+                .map(|comment| comment.text.trim_start_matches('#').trim().to_owned())
+                .filter(|comment| !comment.is_empty());
+        }
+    }
+
+    aliases
+        .into_iter()
+        .map(|(register, alias)| Completion {
+            label: format!("R{register}"),
+            kind: CompletionKind::Variable,
+            insert_text: register.to_string(),
+            detail: alias.unwrap_or_default(),
+            documentation: String::new(),
+            snippet: false,
+        })
+        .collect()
+}
@@ -0,0 +1,78 @@
+//! Parse errors of the model, located on the token that caused them.
+
+use ramemu::{errors::ParseErrorKind, parser::parse};
+
+use crate::{
+    monaco_ram::instruction,
+    syntax::{self, Line, Location, Operand, Token},
+};
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Diagnostic {
+    pub location: Location,
+    pub message: String,
+    pub kind: ParseErrorKind,
+}
+
+pub fn diagnostics(code: &str) -> Vec<Diagnostic> {
+    let Err(errors) = parse(code) else {
+        return Vec::new();
+    };
+    let lines: Vec<_> = syntax::scan(code).collect();
+    errors
+        .into_iter()
+        .filter_map(|error| {
+            let line = lines.get(error.line.checked_sub(1)?)?;
+            let location = match culprit(&lines, line).or_else(|| line.code()) {
+                Some(token) => Location::new(line, token),
+                None => Location {
+                    line: line.number,
+                    start_column: 1,
+                    end_column: syntax::column(line.text, line.text.len()),
+                },
+            };
+            Some(Diagnostic {
+                location,
+                message: error.to_string(),
+                kind: error.kind,
+            })
+        })
+        .collect()
+}
+
+/// `ramemu` errors only carry a line, so the line is checked again to find
+/// the token at fault.
+fn culprit<'a>(lines: &[Line<'a>], line: &Line<'a>) -> Option<Token<'a>> {
+    if let Some(label) = line.label {
+        let defined_before = lines[..line.number - 1]
+            .iter()
+            .any(|other| other.label.is_some_and(|l| l.text == label.text));
+        if defined_before || !syntax::is_label(label.text) {
+            return Some(label);
+        }
+    }
+
+    let opcode = line.opcode?;
+    let Some(instruction) = instruction(opcode.text) else {
+        return Some(opcode);
+    };
+    let Some(operand) = line.operand else {
+        return (!instruction.modes.is_empty()).then_some(opcode);
+    };
+    if instruction.modes.is_empty() {
+        return Some(operand);
+    }
+    match Operand::parse(operand.text) {
+        Some(parsed) if !instruction.accepts(parsed) => return Some(operand),
+        None => return Some(operand),
+        Some(Operand::Label(label)) if !is_defined(lines, label) => return Some(operand),
+        Some(_) => {}
+    }
+    line.rest
+}
+
+pub fn is_defined(lines: &[Line], label: &str) -> bool {
+    lines
+        .iter()
+        .any(|line| line.label.is_some_and(|l| l.text == label))
+}
@@ -0,0 +1,152 @@
+//! Formatter for RAM source: labels in their own column, operands and
+//! comments aligned, consistent keyword case and no runs of blank lines.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{monaco_ram::instruction, syntax};
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum KeywordCase {
+    #[default]
+    Upper,
+    Lower,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct FormatOptions {
+    pub keyword_case: KeywordCase,
+    /// Format the code before it is downloaded with Ctrl+S.
+    pub format_on_save: bool,
+}
+
+/// Formats `code`. Unknown instructions and malformed lines are kept as they
+/// are, so formatting never changes what the parser reports.
+pub fn format(code: &str, options: FormatOptions) -> String {
+    let lines: Vec<_> = syntax::scan(code).collect();
+
+    let label_width = lines
+        .iter()
+        .filter_map(|line| line.label)
+        .map(|label| label.text.chars().count() + 2)
+        .max()
+        .unwrap_or_default();
+    let opcode_width = lines
+        .iter()
+        .filter(|line| line.operand.is_some())
+        .filter_map(|line| line.opcode)
+        .map(|opcode| opcode.text.chars().count() + 1)
+        .max()
+        .unwrap_or_default();
+
+    let code_parts: Vec<_> = lines
+        .iter()
+        .map(|line| {
+            let Some(opcode) = line.opcode else {
+                return line.label.map(|label| format!("{}:", label.text));
+            };
+            let opcode = match (instruction(opcode.text), options.keyword_case) {
+                (Some(_), KeywordCase::Upper) => opcode.text.to_uppercase(),
+                (Some(_), KeywordCase::Lower) => opcode.text.to_lowercase(),
+                (None, _) => opcode.text.to_owned(),
+            };
+            let label = line
+                .label
+                .map_or(String::new(), |label| format!("{}:", label.text));
+            let mut part = format!("{label:label_width$}{opcode}");
+            if let Some(operand) = line.operand {
+                part = format!(
+                    "{part:width$}{}",
+                    operand.text,
+                    width = label_width + opcode_width
+                );
+            }
+            if let Some(rest) = line.rest {
+                part = format!("{part} {}", rest.text);
+            }
+            Some(part)
+        })
+        .collect();
+
+    let comment_column = code_parts
+        .iter()
+        .zip(&lines)
+        .filter(|(_, line)| line.comment.is_some())
+        .filter_map(|(part, _)| part.as_ref())
+        .map(|part| part.chars().count() + 1)
+        .max()
+        .unwrap_or_default();
+
+    let mut formatted = Vec::new();
+    for (part, line) in code_parts.into_iter().zip(&lines) {
+        let text = match (part, line.comment) {
+            (Some(part), Some(comment)) => {
+                format!("{part:comment_column$}{}", comment.text.trim_end())
+            }
+            (Some(part), None) => part,
+            (None, Some(comment)) => comment.text.trim_end().to_owned(),
+            (None, None) => String::new(),
+        };
+        let previous_blank = formatted.last().map_or(true, String::is_empty);
+        if !(text.is_empty() && previous_blank) {
+            formatted.push(text);
+        }
+    }
+    if formatted.last().is_some_and(String::is_empty) {
+        formatted.pop();
+    }
+
+    let mut formatted = formatted.join("\n");
+    if code.ends_with('\n') {
+        formatted.push('\n');
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY: &str = "\n\n  loop:   read 0 # next value\njzero   end\n\n\n\n  write 0\njump loop   # again   \nend:\n   halt\n\n";
+
+    fn lower() -> FormatOptions {
+        FormatOptions {
+            keyword_case: KeywordCase::Lower,
+            ..FormatOptions::default()
+        }
+    }
+
+    #[test]
+    fn aligns_columns() {
+        assert_eq!(
+            format(MESSY, FormatOptions::default()),
+            "loop: READ  0    # next value\n      JZERO end\n\n      WRITE 0\n      JUMP  loop # again\nend:\n      HALT\n"
+        );
+    }
+
+    #[test]
+    fn is_idempotent() {
+        let samples = [
+            MESSY,
+            "",
+            "# only a comment",
+            "a: b: LOAD 1",
+            "LOAD =1 2 3 # extra tokens",
+            "FOO 1\nbar: BAZ\n",
+        ];
+        for sample in samples {
+            for options in [FormatOptions::default(), lower()] {
+                let once = format(sample, options);
+                assert_eq!(format(&once, options), once, "formatting {sample:?} twice");
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_unknown_instructions() {
+        assert_eq!(
+            format("foo 1\nload 2", FormatOptions::default()),
+            "foo  1\nLOAD 2"
+        );
+        assert_eq!(format("LOAD 2", lower()), "load 2");
+    }
+}
@@ -0,0 +1,106 @@
+//! Hover documentation for the instruction or operand under the cursor.
+
+use ramemu::registers::{RegisterId, Registers};
+
+use crate::{
+    monaco_ram::{instruction, Flow},
+    syntax::{self, Line, Operand, Token},
+};
+
+pub struct Hover {
+    /// 1-based columns of the hovered token, end exclusive.
+    pub start_column: usize,
+    pub end_column: usize,
+    /// Markdown paragraphs.
+    pub contents: Vec<String>,
+}
+
+impl Hover {
+    fn new(text: &str, token: Token, contents: Vec<String>) -> Self {
+        Self {
+            start_column: syntax::column(text, token.start),
+            end_column: syntax::column(text, token.end()),
+            contents,
+        }
+    }
+}
+
+/// `registers` are passed while the debugger is paused, to show what an
+/// operand currently refers to.
+pub fn hover(
+    code: &str,
+    line: usize,
+    column: usize,
+    registers: Option<&Registers<i64>>,
+) -> Option<Hover> {
+    let text = code.lines().nth(line.checked_sub(1)?)?;
+    let scanned = syntax::scan_line(line, text);
+    let offset = syntax::offset(text, column);
+
+    if let Some(opcode) = scanned.opcode.filter(|opcode| opcode.contains(offset)) {
+        let instruction = instruction(opcode.text)?;
+        return Some(Hover::new(text, opcode, vec![instruction.documentation()]));
+    }
+
+    let token = scanned.operand.filter(|operand| operand.contains(offset))?;
+    let operand = Operand::parse(token.text)?;
+    let mut contents = vec![describe(code, operand)];
+    let value = registers
+        .and_then(|registers| resolve(operand, |register| registers.get(RegisterId(register))));
+    if let Some(value) = value {
+        contents.push(format!("Current value: `{value}`"));
+    }
+    Some(Hover::new(text, token, contents))
+}
+
+fn describe(code: &str, operand: Operand) -> String {
+    match operand {
+        Operand::Constant(value) => format!("**Constant** `={value}`: the number {value} itself."),
+        Operand::Direct(register) => format!("**Direct** `{register}`: register `R{register}`."),
+        Operand::Indirect(register) => format!(
+            "**Indirect** `*{register}`: the register whose number is stored in `R{register}`."
+        ),
+        Operand::Label(label) => {
+            let definition = syntax::scan(code)
+                .find(|line| line.label.is_some_and(|l| l.text == label))
+                .map(|line| line.number);
+            match definition {
+                Some(number) => format!("**Label** `{label}`, defined on line {number}."),
+                None => format!("**Label** `{label}` is not defined."),
+            }
+        }
+    }
+}
+
+/// Spells out what a register operand refers to, e.g. `R4 = 12 → R12 = 7`,
+/// given the `value` of each register.
+pub fn resolve(operand: Operand, value: impl Fn(usize) -> i64) -> Option<String> {
+    match operand {
+        Operand::Direct(register) => Some(format!("R{register} = {}", value(register))),
+        Operand::Indirect(register) => {
+            let pointer = value(register);
+            let target = match usize::try_from(pointer) {
+                Ok(target) => format!("R{target} = {}", value(target)),
+                Err(_) => "no register".to_owned(),
+            };
+            Some(format!("R{register} = {pointer} → {target}"))
+        }
+        Operand::Constant(_) | Operand::Label(_) => None,
+    }
+}
+
+/// What the instruction on `line` refers to, shown after it while paused,
+/// given the `value` of each register.
+pub fn inline_values(line: &Line, value: impl Fn(usize) -> i64) -> Option<String> {
+    let mut values = Vec::new();
+    let instruction = line.opcode.and_then(|opcode| instruction(opcode.text));
+    let branches =
+        instruction.is_some_and(|i| i.reads_accumulator && matches!(i.flow, Flow::Jump(_)));
+    if branches {
+        values.push(format!("R0 = {}", value(0)));
+    }
+    if let Some(operand) = line.operand().and_then(|operand| resolve(operand, value)) {
+        values.push(operand);
+    }
+    (!values.is_empty()).then(|| values.join(", "))
+}
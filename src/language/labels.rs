@@ -0,0 +1,286 @@
+//! Label resolution for navigation and refactoring. Like `ramemu::parser::parse`,
+//! a label is defined by `name:` at the start of a line, and is referenced by
+//! the operand of any instruction.
+
+use std::fmt::{self, Display};
+
+use crate::{
+    monaco_ram::{instruction, Flow},
+    syntax::{self, Line, Location, Operand, Token},
+};
+
+fn reference<'a>(line: &Line<'a>) -> Option<(&'a str, Token<'a>)> {
+    match line.operand()? {
+        Operand::Label(label) => Some((label, line.operand?)),
+        _ => None,
+    }
+}
+
+/// The label defined or referenced at the cursor.
+pub fn label_at(code: &str, line: usize, column: usize) -> Option<&str> {
+    let text = code.lines().nth(line.checked_sub(1)?)?;
+    let scanned = syntax::scan_line(line, text);
+    let offset = syntax::offset(text, column);
+
+    if let Some(label) = scanned.label {
+        // Includes the trailing `:`
+        if (label.start..=label.end()).contains(&offset) {
+            return Some(label.text);
+        }
+    }
+    reference(&scanned)
+        .filter(|(_, token)| token.contains(offset))
+        .map(|(label, _)| label)
+}
+
+/// Where `label` is defined. Only the first definition counts, later ones are
+/// parse errors anyway.
+pub fn definition(code: &str, label: &str) -> Option<Location> {
+    syntax::scan(code).find_map(|line| {
+        let token = line.label.filter(|token| token.text == label)?;
+        Some(Location::new(&line, token))
+    })
+}
+
+/// Every operand referring to `label`, optionally preceded by its definition.
+pub fn references(code: &str, label: &str, include_definition: bool) -> Vec<Location> {
+    let definition = include_definition
+        .then(|| definition(code, label))
+        .flatten();
+    let references = syntax::scan(code).filter_map(|line| {
+        let (_, token) = reference(&line).filter(|(name, _)| *name == label)?;
+        Some(Location::new(&line, token))
+    });
+    definition.into_iter().chain(references).collect()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JumpKind {
+    Unconditional,
+    Zero,
+    Positive,
+}
+
+/// A jump instruction and where it continues when taken.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Jump {
+    pub kind: JumpKind,
+    pub from: usize,
+    /// Line of the label.
+    pub to: usize,
+    /// Line of the instruction the label belongs to, which differs from `to`
+    /// for a label on a line of its own.
+    pub target: usize,
+}
+
+pub fn jumps(code: &str) -> Vec<Jump> {
+    let lines: Vec<_> = syntax::scan(code).collect();
+    lines
+        .iter()
+        .filter_map(|line| {
+            let Flow::Jump(kind) = instruction(line.opcode?.text)?.flow else {
+                return None;
+            };
+            let (label, _) = reference(line)?;
+            let to = lines
+                .iter()
+                .position(|other| other.label.is_some_and(|l| l.text == label))?;
+            let target = lines[to..]
+                .iter()
+                .find(|other| other.opcode.is_some())
+                .map_or(lines[to].number, |other| other.number);
+            Some(Jump {
+                kind,
+                from: line.number,
+                to: lines[to].number,
+                target,
+            })
+        })
+        .collect()
+}
+
+/// The lines from a label up to the next one, as shown in the outline.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Block<'a> {
+    pub label: &'a str,
+    pub location: Location,
+    /// Last line of the block that is not blank.
+    pub end_line: usize,
+    pub instructions: usize,
+}
+
+pub fn blocks(code: &str) -> Vec<Block<'_>> {
+    let mut blocks: Vec<Block> = Vec::new();
+    for line in syntax::scan(code) {
+        if let Some(label) = line.label {
+            blocks.push(Block {
+                label: label.text,
+                location: Location::new(&line, label),
+                end_line: line.number,
+                instructions: 0,
+            });
+        }
+        let Some(block) = blocks.last_mut() else {
+            continue;
+        };
+        if line.opcode.is_some() {
+            block.instructions += 1;
+        }
+        if !line.text.trim().is_empty() {
+            block.end_line = line.number;
+        }
+    }
+    blocks
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum RenameError {
+    NotALabel,
+    InvalidName(String),
+    Keyword(String),
+    Exists(String),
+}
+
+impl Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotALabel => write!(f, "Only labels can be renamed"),
+            Self::InvalidName(name) => write!(f, "`{name}` is not a valid label name"),
+            Self::Keyword(name) => write!(f, "`{name}` is an instruction"),
+            Self::Exists(name) => write!(f, "A label `{name}` already exists"),
+        }
+    }
+}
+
+/// The label at the cursor and the token to show the rename box on.
+pub fn rename_target(
+    code: &str,
+    line: usize,
+    column: usize,
+) -> Result<(&str, Location), RenameError> {
+    let label = label_at(code, line, column).ok_or(RenameError::NotALabel)?;
+    let location = references(code, label, true)
+        .into_iter()
+        .find(|location| {
+            // A label and a jump to it can share a line, as in `l: JUMP l`
+            location.line == line && (location.start_column..=location.end_column).contains(&column)
+        })
+        .ok_or(RenameError::NotALabel)?;
+    Ok((label, location))
+}
+
+/// Every token to replace with `new_name` to rename the label at the cursor,
+/// its definition included.
+pub fn rename(
+    code: &str,
+    line: usize,
+    column: usize,
+    new_name: &str,
+) -> Result<Vec<Location>, RenameError> {
+    let (label, _) = rename_target(code, line, column)?;
+    if new_name == label {
+        return Ok(Vec::new());
+    }
+    if !syntax::is_label(new_name) {
+        return Err(RenameError::InvalidName(new_name.to_owned()));
+    }
+    if instruction(new_name).is_some() {
+        return Err(RenameError::Keyword(new_name.to_owned()));
+    }
+    if definition(code, new_name).is_some() {
+        return Err(RenameError::Exists(new_name.to_owned()));
+    }
+    Ok(references(code, label, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "start: READ 0\nJZERO end\nJUMP start # again\nend: HALT";
+
+    fn location(line: usize, start_column: usize, end_column: usize) -> Location {
+        Location {
+            line,
+            start_column,
+            end_column,
+        }
+    }
+
+    #[test]
+    fn label_at_cursor() {
+        assert_eq!(label_at(CODE, 1, 1), Some("start"));
+        // On the `:` of the definition
+        assert_eq!(label_at(CODE, 1, 6), Some("start"));
+        assert_eq!(label_at(CODE, 2, 8), Some("end"));
+        assert_eq!(label_at(CODE, 1, 9), None);
+        assert_eq!(label_at(CODE, 3, 14), None);
+        assert_eq!(label_at(CODE, 9, 1), None);
+    }
+
+    #[test]
+    fn definition_is_the_first_one() {
+        assert_eq!(definition(CODE, "end"), Some(location(4, 1, 4)));
+        assert_eq!(definition("a: HALT\na: HALT", "a"), Some(location(1, 1, 2)));
+        assert_eq!(definition(CODE, "missing"), None);
+    }
+
+    #[test]
+    fn references_of_a_label() {
+        assert_eq!(references(CODE, "start", false), [location(3, 6, 11)]);
+        assert_eq!(
+            references(CODE, "start", true),
+            [location(1, 1, 6), location(3, 6, 11)]
+        );
+        assert_eq!(references(CODE, "READ", true), []);
+    }
+
+    #[test]
+    fn rename_target_is_the_token_under_the_cursor() {
+        let code = "loop: JUMP loop";
+        let definition = Location {
+            line: 1,
+            start_column: 1,
+            end_column: 5,
+        };
+        let reference = Location {
+            line: 1,
+            start_column: 12,
+            end_column: 16,
+        };
+        assert_eq!(rename_target(code, 1, 3), Ok(("loop", definition)));
+        assert_eq!(rename_target(code, 1, 5), Ok(("loop", definition)));
+        assert_eq!(rename_target(code, 1, 14), Ok(("loop", reference)));
+        assert_eq!(rename_target(code, 1, 8), Err(RenameError::NotALabel));
+    }
+
+    #[test]
+    fn rename_replaces_definition_and_references() {
+        assert_eq!(
+            rename(CODE, 3, 7, "top"),
+            Ok(vec![location(1, 1, 6), location(3, 6, 11)])
+        );
+        assert_eq!(rename(CODE, 3, 7, "start"), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn rename_rejects_colliding_names() {
+        assert_eq!(
+            rename(CODE, 1, 1, "end"),
+            Err(RenameError::Exists("end".to_owned()))
+        );
+        assert_eq!(
+            rename(CODE, 1, 1, "jump"),
+            Err(RenameError::Keyword("jump".to_owned()))
+        );
+        assert_eq!(
+            rename(CODE, 1, 1, "JMP"),
+            Err(RenameError::Keyword("JMP".to_owned()))
+        );
+        assert_eq!(
+            rename(CODE, 1, 1, "2nd"),
+            Err(RenameError::InvalidName("2nd".to_owned()))
+        );
+        assert_eq!(rename(CODE, 1, 9, "x"), Err(RenameError::NotALabel));
+    }
+}
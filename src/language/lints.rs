@@ -0,0 +1,254 @@
+//! Warnings about programs that parse but are likely wrong.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    language::{
+        cfg::{Program, Step},
+        labels::JumpKind,
+    },
+    monaco_ram::Flow,
+    syntax::{Line, Location, Operand, Token},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub enum Lint {
+    Unreachable,
+    UnusedLabel,
+    NoHalt,
+    ReadBeforeWrite,
+    DivisionByZero,
+    SelfLoop,
+}
+
+impl Lint {
+    pub const ALL: [Self; 6] = [
+        Self::Unreachable,
+        Self::UnusedLabel,
+        Self::NoHalt,
+        Self::ReadBeforeWrite,
+        Self::DivisionByZero,
+        Self::SelfLoop,
+    ];
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Unreachable => "Unreachable instructions",
+            Self::UnusedLabel => "Labels never jumped to",
+            Self::NoHalt => "No reachable HALT",
+            Self::ReadBeforeWrite => "Registers read before written",
+            Self::DivisionByZero => "Division by constant zero",
+            Self::SelfLoop => "Jumps to themselves",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Warning {
+    pub lint: Lint,
+    pub location: Location,
+    pub message: String,
+}
+
+/// Runs the `enabled` lints over `code`, which is expected to parse.
+pub fn lint(code: &str, enabled: impl Fn(Lint) -> bool) -> Vec<Warning> {
+    let program = Program::new(code);
+    let Program {
+        lines,
+        steps,
+        labels,
+        successors,
+    } = &program;
+    let reachable = program.reachable();
+
+    let mut warnings = Vec::new();
+    let mut warn = |lint: Lint, line: &Line, token: Token, message: String| {
+        if enabled(lint) {
+            warnings.push(Warning {
+                lint,
+                location: Location::new(line, token),
+                message,
+            });
+        }
+    };
+
+    for (step, _) in steps.iter().zip(&reachable).filter(|(_, &r)| !r) {
+        let token = step.line.code().unwrap_or(step.opcode);
+        warn(
+            Lint::Unreachable,
+            &step.line,
+            token,
+            "Unreachable instruction".to_owned(),
+        );
+    }
+
+    let used: BTreeSet<_> = lines
+        .iter()
+        .filter_map(|line| match line.operand()? {
+            Operand::Label(label) => Some(label),
+            _ => None,
+        })
+        .collect();
+    for line in lines {
+        if let Some(label) = line.label.filter(|label| !used.contains(label.text)) {
+            let message = format!("Label `{}` is never jumped to", label.text);
+            warn(Lint::UnusedLabel, line, label, message);
+        }
+    }
+
+    let halts = steps
+        .iter()
+        .zip(&reachable)
+        .any(|(step, &reachable)| reachable && step.instruction.flow == Flow::Halt);
+    if let Some(first) = steps.first().filter(|_| !halts) {
+        let message = "No HALT is reachable, the program never stops properly".to_owned();
+        warn(Lint::NoHalt, &first.line, first.opcode, message);
+    }
+
+    for (index, step) in steps.iter().enumerate() {
+        if step.instruction.name == "DIV" && step.line.operand() == Some(Operand::Constant(0)) {
+            let operand = step.line.operand.unwrap_or(step.opcode);
+            warn(
+                Lint::DivisionByZero,
+                &step.line,
+                operand,
+                "Division by zero".to_owned(),
+            );
+        }
+        let unconditional = step.instruction.flow == Flow::Jump(JumpKind::Unconditional);
+        if unconditional && step.target(labels) == Some(index) {
+            let message = "Jumps to itself forever".to_owned();
+            warn(Lint::SelfLoop, &step.line, step.opcode, message);
+        }
+    }
+
+    for (index, registers) in read_before_write(steps, successors) {
+        let step = &steps[index];
+        for register in registers {
+            let message = format!("`R{register}` is read before anything is written to it");
+            let token = if register == 0 {
+                step.opcode
+            } else {
+                step.line.operand.unwrap_or(step.opcode)
+            };
+            warn(Lint::ReadBeforeWrite, &step.line, token, message);
+        }
+    }
+
+    warnings
+}
+
+/// Registers each reachable step reads that are not written on every path
+/// leading to it.
+fn read_before_write(steps: &[Step], successors: &[Vec<usize>]) -> Vec<(usize, Vec<usize>)> {
+    // Registers written on every path to each step, `None` until a path is found
+    let mut written: Vec<Option<BTreeSet<usize>>> = vec![None; steps.len()];
+    if steps.is_empty() {
+        return Vec::new();
+    }
+    written[0] = Some(BTreeSet::new());
+    let mut queue = vec![0];
+    while let Some(index) = queue.pop() {
+        let mut after = written[index].clone().unwrap_or_default();
+        after.extend(steps[index].writes());
+        for &next in &successors[index] {
+            let merged = match &written[next] {
+                Some(before) => before.intersection(&after).copied().collect(),
+                None => after.clone(),
+            };
+            if written[next].as_ref() != Some(&merged) {
+                written[next] = Some(merged);
+                queue.push(next);
+            }
+        }
+    }
+
+    steps
+        .iter()
+        .zip(written)
+        .enumerate()
+        .filter_map(|(index, (step, written))| {
+            let written = written?;
+            let unwritten: Vec<_> = step
+                .reads()
+                .into_iter()
+                .filter(|register| !written.contains(register))
+                .collect();
+            (!unwritten.is_empty()).then_some((index, unwritten))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lints(code: &str) -> Vec<Lint> {
+        lint(code, |_| true).into_iter().map(|w| w.lint).collect()
+    }
+
+    #[test]
+    fn trailing_label_ends_the_program() {
+        let code = "read 1\nload 1\njzero end\nwrite 1\nend:\n";
+        assert_eq!(lints(code), vec![Lint::NoHalt]);
+    }
+
+    #[test]
+    fn empty_program_has_no_warnings() {
+        assert!(lints("").is_empty());
+        assert!(lints("# just a comment\n\n").is_empty());
+    }
+
+    #[test]
+    fn jump_to_the_last_line() {
+        let code = "read 1\nload 1\njgtz end\nwrite 1\nend: halt";
+        assert!(lints(code).is_empty());
+    }
+
+    #[test]
+    fn unreachable_after_jump() {
+        let code = "jump end\nwrite 1\nend: halt";
+        let warnings = lint(code, |_| true);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].lint, Lint::Unreachable);
+        assert_eq!(warnings[0].location.line, 2);
+    }
+
+    #[test]
+    fn read_before_write_follows_every_path() {
+        let code = "read 1\nload 1\njzero skip\nstore 2\nskip: write 2\nhalt";
+        let warnings = lint(code, |lint| lint == Lint::ReadBeforeWrite);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].location.line, 5);
+    }
+
+    #[test]
+    fn disabled_lints_are_skipped() {
+        assert!(lint("jump loop\nloop: jump loop", |_| false).is_empty());
+        assert!(lints("x: jump x").contains(&Lint::SelfLoop));
+    }
+
+    #[test]
+    fn unused_labels() {
+        let code = "read 1\nunused: write 1\nhalt";
+        assert_eq!(lints(code), vec![Lint::UnusedLabel]);
+    }
+
+    #[test]
+    fn division_by_constant_zero() {
+        assert_eq!(
+            lints("load =4\ndiv =0\nwrite 0\nhalt"),
+            vec![Lint::DivisionByZero]
+        );
+        assert!(lints("load =4\ndiv =2\nwrite 0\nhalt").is_empty());
+    }
+
+    #[test]
+    fn registers_written_on_every_path_are_fine() {
+        let code =
+            "read 1\nload 1\njzero zero\nstore 2\njump done\nzero: store 2\ndone: write 2\nhalt";
+        assert!(lints(code).is_empty());
+    }
+}
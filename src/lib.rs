@@ -2,16 +2,34 @@
 
 mod about_popup;
 mod app;
+mod array_view;
+#[cfg(not(feature = "ssr"))]
+mod cfg_view;
 mod code_runner;
 mod header;
 mod io {
     pub mod custom_reader;
     pub mod custom_writer;
     pub mod input;
+    pub mod input_dsl;
+    pub mod io_mode;
     pub mod output;
+    pub mod presets;
 }
 #[cfg(not(feature = "ssr"))]
 mod code_editor;
+mod language {
+    pub mod cfg;
+    pub mod code_actions;
+    pub mod completion;
+    pub mod diagnostics;
+    pub mod format;
+    pub mod hover;
+    pub mod labels;
+    pub mod lints;
+}
+#[cfg(not(feature = "ssr"))]
+mod jump_arrows;
 mod memory;
 mod monaco_ram;
 #[cfg(not(feature = "ssr"))]
@@ -33,7 +51,10 @@ mod code_editor {
         panic!("This component should not be used in server side rendering")
     }
 }
+mod register_history;
+mod settings;
 mod store;
+mod syntax;
 mod utils;
 
 pub use app::App;
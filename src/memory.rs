@@ -2,34 +2,104 @@ use ramemu::registers::RegisterId;
 use yew::prelude::*;
 use yewdux::use_selector;
 
-use crate::store::Store;
+use crate::{
+    array_view::ArrayView,
+    register_history::RegisterWrite,
+    store::{dispatch, run_dispatch, RunStore, Store},
+};
+#[cfg(not(feature = "ssr"))]
+use crate::{cfg_view::CfgView, utils::jump_to_line};
 
 const WINDOW_LENGTH: usize = 100;
 const STEP_SIZE: usize = 50;
 
+const TIMELINE_WIDTH: f64 = 200.;
+const TIMELINE_HEIGHT: f64 = 40.;
+const TIMELINE_PADDING: f64 = 4.;
+
 #[function_component]
 pub fn Memory() -> Html {
-    let registers = use_selector(|s: &Store| s.get_registers().clone());
+    let registers = use_selector(|s: &RunStore| s.registers.clone());
+    let pinned = use_selector(|s: &Store| s.pinned_registers.clone());
+    let inspected_step = *use_selector(|s: &Store| s.inspected_step);
+    let history = use_selector(|s: &Store| s.register_history.clone());
 
     let starting_index = use_state(|| 0);
+    let selected = use_state_eq(|| None::<usize>);
+    let diff_mode = use_state_eq(|| false);
+    let show_array = use_state_eq(|| false);
+    let show_graph = use_state_eq(|| false);
+    let diff = (*pinned).as_ref().filter(|_| *diff_mode);
 
     let register_entries = (0..WINDOW_LENGTH)
         .map(|i| {
             let index = *starting_index + i;
-            let value = registers.get(RegisterId(index));
+            let value = match inspected_step {
+                Some(step) => history.value_at(index, step),
+                None => registers.get(RegisterId(index)),
+            };
             let mut class = "register".to_string();
             if index == 0 {
                 class += " acc";
             }
+            if *selected == Some(index) {
+                class += " selected";
+            }
+            let pinned_value = diff.map(|pinned| pinned.get(RegisterId(index)));
+            if pinned_value.is_some_and(|pinned| pinned != value) {
+                class += " changed";
+            }
+            let onclick = {
+                let selected = selected.clone();
+                Callback::from(move |_: MouseEvent| {
+                    selected.set((*selected != Some(index)).then_some(index));
+                })
+            };
             html! {
-              <div class={class} key={index} >
+              <div class={class} key={index} {onclick}>
                 <div class="register-num"><p>{format!("{index}")}</p></div>
                 <div class="register-val">{value.to_string()}</div>
+                if let Some(pinned_value) = pinned_value {
+                  <div class="register-val register-pinned">{pinned_value.to_string()}</div>
+                }
               </div>
             }
         })
         .collect::<Html>();
 
+    let on_pin = Callback::from(|_: MouseEvent| {
+        let registers = run_dispatch().get().registers.clone();
+        dispatch().reduce_mut(|s: &mut Store| s.pinned_registers = Some(registers));
+    });
+    let on_unpin = Callback::from({
+        let diff_mode = diff_mode.clone();
+        move |_: MouseEvent| {
+            diff_mode.set(false);
+            dispatch().reduce_mut(|s: &mut Store| s.pinned_registers = None);
+        }
+    });
+    let on_toggle_diff = Callback::from({
+        let diff_mode = diff_mode.clone();
+        move |_: MouseEvent| diff_mode.set(!*diff_mode)
+    });
+    let on_show_current = Callback::from(|_: MouseEvent| {
+        dispatch().reduce_mut(|s: &mut Store| s.inspected_step = None);
+    });
+    let on_toggle_array = Callback::from({
+        let show_array = show_array.clone();
+        move |_: MouseEvent| show_array.set(!*show_array)
+    });
+    let on_toggle_graph = Callback::from({
+        let show_graph = show_graph.clone();
+        move |_: MouseEvent| show_graph.set(!*show_graph)
+    });
+
+    // The graph is built from the editor model, which only exists in the browser
+    #[cfg(not(feature = "ssr"))]
+    let graph = html! { <CfgView /> };
+    #[cfg(feature = "ssr")]
+    let graph = html! {};
+
     #[allow(unused_variables)]
     let on_previous_click = Callback::from({
         let starting_index = starting_index.clone();
@@ -46,11 +116,153 @@ pub fn Memory() -> Html {
         <div class="registers-container">
           // <button onclick={on_previous_click}>{"Previous"}</button>
           // <button onclick={on_next_click}>{"Next"}</button>
+          if let Some(register) = *selected {
+            <RegisterTimeline {register} />
+          }
+          if let Some(step) = inspected_step {
+            <div class="memory-inspected">
+              {format!("Values after step {step}")}
+              <button class="memory-btn" onclick={on_show_current}>{"Current"}</button>
+            </div>
+          }
+          <div class="memory-toolbar">
+            <button class="memory-btn" onclick={on_pin}>{"Pin"}</button>
+            if pinned.is_some() {
+              <button
+                class={classes!("memory-btn", diff_mode.then_some("active"))}
+                onclick={on_toggle_diff}
+              >
+                {"Diff"}
+              </button>
+              <button class="memory-btn" onclick={on_unpin}>{"Unpin"}</button>
+            }
+            <button
+              class={classes!("memory-btn", show_array.then_some("active"))}
+              onclick={on_toggle_array}
+            >
+              {"Array"}
+            </button>
+            <button
+              class={classes!("memory-btn", show_graph.then_some("active"))}
+              onclick={on_toggle_graph}
+            >
+              {"Graph"}
+            </button>
+          </div>
+          if *show_array {
+            <ArrayView />
+          }
+          if *show_graph {
+            {graph}
+          }
           <div class="register acc">
             <div class="register-num"><p>{"R"}</p></div>
             <div class="register-val">{"Value"}</div>
+            if diff.is_some() {
+              <div class="register-val register-pinned">{"Pinned"}</div>
+            }
           </div>
           {register_entries}
         </div>
     }
 }
+
+#[derive(Clone, PartialEq, Properties)]
+struct TimelineProps {
+    register: usize,
+}
+
+/// Inspects the step that made a write, which moves the debug line highlight
+/// to its instruction while the run itself stays where it is.
+fn reveal_write(write: RegisterWrite) -> Callback<MouseEvent> {
+    Callback::from(move |_: MouseEvent| {
+        dispatch().reduce_mut(|s: &mut Store| s.inspected_step = Some(write.step));
+        #[cfg(not(feature = "ssr"))]
+        jump_to_line(write.line);
+    })
+}
+
+#[function_component]
+fn RegisterTimeline(props: &TimelineProps) -> Html {
+    let register = props.register;
+    let history = use_selector(|s: &Store| s.register_history.clone());
+    let writes = history.writes(register);
+
+    let title = html! { <div class="timeline-title">{format!("R{register} history")}</div> };
+
+    if writes.is_empty() {
+        return html! {
+          <div class="register-timeline">
+            {title}
+            <div class="timeline-empty">{"No writes recorded"}</div>
+          </div>
+        };
+    }
+
+    let (min, max) = writes
+        .iter()
+        .fold((i64::MAX, i64::MIN), |(min, max), write| {
+            (min.min(write.value), max.max(write.value))
+        });
+    let span = (max as f64 - min as f64).max(1.);
+    let last = (writes.len() - 1).max(1) as f64;
+    let inner_width = TIMELINE_WIDTH - 2. * TIMELINE_PADDING;
+    let inner_height = TIMELINE_HEIGHT - 2. * TIMELINE_PADDING;
+
+    let points = writes
+        .iter()
+        .enumerate()
+        .map(|(i, write)| {
+            let x = TIMELINE_PADDING + inner_width * i as f64 / last;
+            let y =
+                TIMELINE_PADDING + inner_height * (1. - (write.value as f64 - min as f64) / span);
+            (x, y, write)
+        })
+        .collect::<Vec<_>>();
+
+    let polyline = points
+        .iter()
+        .map(|(x, y, _)| format!("{x:.1},{y:.1}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let dots = points.iter().map(|&(x, y, write)| {
+        let hint = format!("step {}, line {}: {}", write.step, write.line, write.value);
+        html! {
+          <circle
+            class="timeline-point"
+            cx={format!("{x:.1}")}
+            cy={format!("{y:.1}")}
+            r="2.5"
+            onclick={reveal_write(*write)}
+          >
+            <title>{hint}</title>
+          </circle>
+        }
+    });
+
+    let entries = writes.iter().map(|write| {
+        html! {
+          <div class="timeline-entry" onclick={reveal_write(*write)}>
+            <span class="timeline-step">{format!("#{}", write.step)}</span>
+            <span class="timeline-line">{format!("L{}", write.line)}</span>
+            <span class="timeline-value">{write.value}</span>
+          </div>
+        }
+    });
+
+    html! {
+      <div class="register-timeline">
+        {title}
+        <svg
+          class="timeline-chart"
+          viewBox={format!("0 0 {TIMELINE_WIDTH} {TIMELINE_HEIGHT}")}
+          preserveAspectRatio="none"
+        >
+          <polyline class="timeline-path" points={polyline} />
+          { for dots }
+        </svg>
+        <div class="timeline-entries">{ for entries }</div>
+      </div>
+    }
+}
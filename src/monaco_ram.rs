@@ -1,16 +1,284 @@
-use js_sys::Object;
+use js_sys::{Array, Object, Reflect};
 use monaco::sys::editor;
 use monaco::sys::languages;
 use monaco::sys::languages::ILanguageExtensionPoint;
-use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen::{closure::WasmClosure, prelude::*, JsCast};
+
+use crate::{
+    language::{
+        code_actions::{code_actions, CodeAction},
+        completion::{completions, Completion},
+        format::format,
+        hover::{hover, Hover},
+        labels::{self, Block, JumpKind, RenameError},
+    },
+    store::{dispatch, run_dispatch},
+    syntax::{Location, Operand},
+};
 
 pub const LANG_ID: &str = "ram";
 pub const THEME: &str = "ram-theme";
 pub const THEME_JSON: &str = include_str!("../assets/theme.json");
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OperandMode {
+    /// `=n`
+    Constant,
+    /// `n`
+    Direct,
+    /// `*n`
+    Indirect,
+    Label,
+}
+
+impl OperandMode {
+    pub fn of(operand: Operand) -> Self {
+        match operand {
+            Operand::Constant(_) => Self::Constant,
+            Operand::Direct(_) => Self::Direct,
+            Operand::Indirect(_) => Self::Indirect,
+            Operand::Label(_) => Self::Label,
+        }
+    }
+
+    pub fn syntax(self) -> &'static str {
+        match self {
+            Self::Constant => "=n",
+            Self::Direct => "n",
+            Self::Indirect => "*n",
+            Self::Label => "label",
+        }
+    }
+}
+
+const VALUE: &[OperandMode] = &[
+    OperandMode::Constant,
+    OperandMode::Direct,
+    OperandMode::Indirect,
+];
+const REGISTER: &[OperandMode] = &[OperandMode::Direct, OperandMode::Indirect];
+const LABEL: &[OperandMode] = &[OperandMode::Label];
+
+/// Register an instruction stores its result in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Writes {
+    Nothing,
+    Accumulator,
+    /// The register named by the operand, directly or through a pointer.
+    Operand,
+}
+
+/// Where execution continues after an instruction.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Flow {
+    Next,
+    /// To the label, and for a conditional jump otherwise to the next instruction.
+    Jump(JumpKind),
+    Halt,
+}
+
+/// Everything the editor knows about an instruction. The tokenizer,
+/// completion, hover, diagnostics and the help text are all built from
+/// [`INSTRUCTIONS`], so an alias or instruction only has to be added there.
+pub struct Instruction {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    /// Operand forms the instruction accepts, empty if it takes none.
+    pub modes: &'static [OperandMode],
+    pub reads_accumulator: bool,
+    /// Whether the value of the operand is used, rather than only the
+    /// register it names.
+    pub reads_operand: bool,
+    pub writes: Writes,
+    pub flow: Flow,
+    pub description: &'static str,
+}
+
+impl Instruction {
+    /// Every spelling of the instruction, the canonical name first.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> {
+        std::iter::once(self.name).chain(self.aliases.iter().copied())
+    }
+
+    pub fn accepts(&self, operand: Operand) -> bool {
+        self.modes.contains(&OperandMode::of(operand))
+    }
+
+    /// Placeholder for the operand in the signature, empty if there is none.
+    pub fn operand(&self) -> &'static str {
+        match self.modes {
+            [] => "",
+            LABEL => "label",
+            REGISTER => "register",
+            _ => "operand",
+        }
+    }
+
+    /// Accepted operand forms, such as `` `=n`, `n` or `*n` ``.
+    pub fn operand_forms(&self) -> String {
+        let forms: Vec<_> = self
+            .modes
+            .iter()
+            .map(|mode| format!("`{}`", mode.syntax()))
+            .collect();
+        match forms.split_last() {
+            None => String::new(),
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} or {last}", rest.join(", ")),
+        }
+    }
+
+    /// Markdown shown when hovering the instruction.
+    pub fn documentation(&self) -> String {
+        let mut doc = format!("**{}** {}", self.name, self.operand());
+        if !self.aliases.is_empty() {
+            doc += &format!(" (also `{}`)", self.aliases.join("`, `"));
+        }
+        if !self.modes.is_empty() {
+            doc += &format!("\n\nOperand: {}.", self.operand_forms());
+        }
+        doc + "\n\n" + self.description
+    }
+}
+
+pub const INSTRUCTIONS: &[Instruction] = &[
+    Instruction {
+        name: "LOAD",
+        aliases: &[],
+        modes: VALUE,
+        reads_accumulator: false,
+        reads_operand: true,
+        writes: Writes::Accumulator,
+        flow: Flow::Next,
+        description: "Loads the operand into the accumulator `R0`.",
+    },
+    Instruction {
+        name: "STORE",
+        aliases: &[],
+        modes: REGISTER,
+        reads_accumulator: true,
+        reads_operand: false,
+        writes: Writes::Operand,
+        flow: Flow::Next,
+        description: "Stores the accumulator `R0` into the register.",
+    },
+    Instruction {
+        name: "ADD",
+        aliases: &[],
+        modes: VALUE,
+        reads_accumulator: true,
+        reads_operand: true,
+        writes: Writes::Accumulator,
+        flow: Flow::Next,
+        description: "Adds the operand to the accumulator `R0`.",
+    },
+    Instruction {
+        name: "SUB",
+        aliases: &[],
+        modes: VALUE,
+        reads_accumulator: true,
+        reads_operand: true,
+        writes: Writes::Accumulator,
+        flow: Flow::Next,
+        description: "Subtracts the operand from the accumulator `R0`.",
+    },
+    Instruction {
+        name: "MUL",
+        aliases: &[],
+        modes: VALUE,
+        reads_accumulator: true,
+        reads_operand: true,
+        writes: Writes::Accumulator,
+        flow: Flow::Next,
+        description: "Multiplies the accumulator `R0` by the operand.",
+    },
+    Instruction {
+        name: "DIV",
+        aliases: &[],
+        modes: VALUE,
+        reads_accumulator: true,
+        reads_operand: true,
+        writes: Writes::Accumulator,
+        flow: Flow::Next,
+        description: "Divides the accumulator `R0` by the operand, discarding the remainder.",
+    },
+    Instruction {
+        name: "READ",
+        aliases: &["INPUT"],
+        modes: REGISTER,
+        reads_accumulator: false,
+        reads_operand: false,
+        writes: Writes::Operand,
+        flow: Flow::Next,
+        description: "Reads the next input value into the register.",
+    },
+    Instruction {
+        name: "WRITE",
+        aliases: &["OUTPUT"],
+        modes: VALUE,
+        reads_accumulator: false,
+        reads_operand: true,
+        writes: Writes::Nothing,
+        flow: Flow::Next,
+        description: "Writes the operand to the output.",
+    },
+    Instruction {
+        name: "JUMP",
+        aliases: &["JMP"],
+        modes: LABEL,
+        reads_accumulator: false,
+        reads_operand: false,
+        writes: Writes::Nothing,
+        flow: Flow::Jump(JumpKind::Unconditional),
+        description: "Continues at the label.",
+    },
+    Instruction {
+        name: "JZERO",
+        aliases: &["JZ"],
+        modes: LABEL,
+        reads_accumulator: true,
+        reads_operand: false,
+        writes: Writes::Nothing,
+        flow: Flow::Jump(JumpKind::Zero),
+        description: "Continues at the label if the accumulator `R0` is zero.",
+    },
+    Instruction {
+        name: "JGTZ",
+        aliases: &["JGZ"],
+        modes: LABEL,
+        reads_accumulator: true,
+        reads_operand: false,
+        writes: Writes::Nothing,
+        flow: Flow::Jump(JumpKind::Positive),
+        description: "Continues at the label if the accumulator `R0` is greater than zero.",
+    },
+    Instruction {
+        name: "HALT",
+        aliases: &[],
+        modes: &[],
+        reads_accumulator: false,
+        reads_operand: false,
+        writes: Writes::Nothing,
+        flow: Flow::Halt,
+        description: "Stops the program.",
+    },
+];
+
+/// Looks up an instruction by its name or an alias, ignoring case.
+pub fn instruction(name: &str) -> Option<&'static Instruction> {
+    INSTRUCTIONS
+        .iter()
+        .find(|instruction| instruction.names().any(|n| n.eq_ignore_ascii_case(name)))
+}
+
 pub fn register_ram() {
     languages::register(&language());
-    languages::set_monarch_tokens_provider(LANG_ID, &make_tokens_provider().into());
+    let keywords: Array = INSTRUCTIONS
+        .iter()
+        .flat_map(Instruction::names)
+        .map(JsValue::from)
+        .collect();
+    languages::set_monarch_tokens_provider(LANG_ID, &make_tokens_provider(&keywords).into());
     if let Err(err) = editor::define_theme(THEME, &load_theme(THEME_JSON).unchecked_into()) {
         gloo::console::error!("Defining theme failed", err);
     }
@@ -18,6 +286,27 @@ pub fn register_ram() {
         LANG_ID,
         &completion_items_provider().unchecked_into(),
     );
+    languages::register_hover_provider(LANG_ID, &hover_provider().unchecked_into());
+    languages::register_definition_provider(LANG_ID, &definition_provider().unchecked_into());
+    languages::register_reference_provider(LANG_ID, &reference_provider().unchecked_into());
+    languages::register_rename_provider(LANG_ID, &rename_provider().unchecked_into());
+    languages::register_document_formatting_edit_provider(
+        LANG_ID,
+        &formatting_provider().unchecked_into(),
+    );
+    languages::register_code_action_provider(
+        LANG_ID,
+        &code_action_provider().unchecked_into(),
+        None,
+    );
+    languages::register_document_symbol_provider(
+        LANG_ID,
+        &make_provider(blocks_provider(), make_document_symbol_provider).unchecked_into(),
+    );
+    languages::register_folding_range_provider(
+        LANG_ID,
+        &make_provider(blocks_provider(), make_folding_range_provider).unchecked_into(),
+    );
 }
 
 fn language() -> ILanguageExtensionPoint {
@@ -26,16 +315,239 @@ fn language() -> ILanguageExtensionPoint {
     lang
 }
 
+fn completion_items_provider() -> Object {
+    let provide = Closure::<dyn Fn(String, usize, usize) -> Array>::new(
+        |code: String, line: usize, column: usize| {
+            completions(&code, line, column)
+                .into_iter()
+                .map(|completion: Completion| {
+                    js_object(&[
+                        ("label", completion.label.into()),
+                        ("kind", completion.kind.name().into()),
+                        ("insertText", completion.insert_text.into()),
+                        ("detail", completion.detail.into()),
+                        ("documentation", completion.documentation.into()),
+                        ("snippet", completion.snippet.into()),
+                    ])
+                })
+                .collect()
+        },
+    );
+    make_provider(provide, make_completion_items_provider)
+}
+
+fn hover_provider() -> Object {
+    let provide = Closure::<dyn Fn(String, usize, usize) -> JsValue>::new(
+        |code: String, line: usize, column: usize| {
+            let run = run_dispatch().get();
+            let registers = run.paused.then_some(&run.registers);
+            hover(&code, line, column, registers).map_or(JsValue::NULL, |hover: Hover| {
+                let contents: Array = hover.contents.iter().map(JsValue::from).collect();
+                js_object(&[
+                    ("startColumn", hover.start_column.into()),
+                    ("endColumn", hover.end_column.into()),
+                    ("contents", contents.into()),
+                ])
+            })
+        },
+    );
+    make_provider(provide, make_hover_provider)
+}
+
+fn definition_provider() -> Object {
+    let provide = Closure::<dyn Fn(String, usize, usize) -> Array>::new(
+        |code: String, line: usize, column: usize| {
+            labels::label_at(&code, line, column)
+                .and_then(|label| labels::definition(&code, label))
+                .into_iter()
+                .map(location)
+                .collect()
+        },
+    );
+    make_provider(provide, make_definition_provider)
+}
+
+fn reference_provider() -> Object {
+    let provide = Closure::<dyn Fn(String, usize, usize, bool) -> Array>::new(
+        |code: String, line: usize, column: usize, include_definition: bool| {
+            labels::label_at(&code, line, column)
+                .map(|label| labels::references(&code, label, include_definition))
+                .unwrap_or_default()
+                .into_iter()
+                .map(location)
+                .collect()
+        },
+    );
+    make_provider(provide, make_reference_provider)
+}
+
+fn rename_provider() -> Object {
+    let resolve = Closure::<dyn Fn(String, usize, usize) -> JsValue>::new(
+        |code: String, line: usize, column: usize| {
+            let target = labels::rename_target(&code, line, column);
+            target.map_or_else(rejection, |(label, target)| {
+                js_object(&[("text", label.into()), ("location", location(target))])
+            })
+        },
+    );
+    let rename = Closure::<dyn Fn(String, usize, usize, String) -> JsValue>::new(
+        |code: String, line: usize, column: usize, new_name: String| {
+            let locations = labels::rename(&code, line, column, &new_name);
+            locations.map_or_else(rejection, |locations| {
+                let locations: Array = locations.into_iter().map(location).collect();
+                js_object(&[("locations", locations.into())])
+            })
+        },
+    );
+    let provider = make_rename_provider(resolve.as_ref(), rename.as_ref());
+    // Leaked for the same reason as in `make_provider`
+    resolve.forget();
+    rename.forget();
+    provider
+}
+
+fn formatting_provider() -> Object {
+    let provide = Closure::<dyn Fn(String) -> String>::new(|code: String| {
+        format(&code, dispatch().get().format_options)
+    });
+    make_provider(provide, make_formatting_provider)
+}
+
+fn code_action_provider() -> Object {
+    let provide = Closure::<dyn Fn(String, usize, usize) -> Array>::new(
+        |code: String, first_line: usize, last_line: usize| {
+            code_actions(&code, first_line, last_line)
+                .into_iter()
+                .map(|action: CodeAction| {
+                    let edits: Array = action
+                        .edits
+                        .into_iter()
+                        .map(|edit| {
+                            js_object(&[
+                                ("location", location(edit.location)),
+                                ("text", edit.text.into()),
+                            ])
+                        })
+                        .collect();
+                    js_object(&[
+                        ("title", action.title.into()),
+                        ("edits", edits.into()),
+                        ("preferred", action.preferred.into()),
+                    ])
+                })
+                .collect()
+        },
+    );
+    make_provider(provide, make_code_action_provider)
+}
+
+/// Label blocks of the code, shared by the outline and folding providers.
+fn blocks_provider() -> Closure<dyn Fn(String) -> Array> {
+    Closure::new(|code: String| {
+        labels::blocks(&code)
+            .into_iter()
+            .map(|block: Block| {
+                let detail = match block.instructions {
+                    1 => "1 instruction".to_owned(),
+                    count => format!("{count} instructions"),
+                };
+                js_object(&[
+                    ("label", block.label.into()),
+                    ("location", location(block.location)),
+                    ("endLine", block.end_line.into()),
+                    ("detail", detail.into()),
+                ])
+            })
+            .collect()
+    })
+}
+
+/// Hands `provide` to the JS factory building the provider object. Providers
+/// are registered once and live as long as the page, so the closure is leaked.
+fn make_provider<F: ?Sized + WasmClosure>(
+    provide: Closure<F>,
+    make: fn(&JsValue) -> Object,
+) -> Object {
+    let provider = make(provide.as_ref());
+    provide.forget();
+    provider
+}
+
+fn location(location: Location) -> JsValue {
+    js_object(&[
+        ("line", location.line.into()),
+        ("startColumn", location.start_column.into()),
+        ("endColumn", location.end_column.into()),
+    ])
+}
+
+fn rejection(err: RenameError) -> JsValue {
+    js_object(&[("rejectReason", err.to_string().into())])
+}
+
+/// Builds a plain object for provider results Monaco reads by field name.
+fn js_object(fields: &[(&str, JsValue)]) -> JsValue {
+    let object = Object::new();
+    for (key, value) in fields {
+        if let Err(err) = Reflect::set(&object, &JsValue::from_str(key), value) {
+            gloo::console::error!("Failed to set provider result field", err);
+        }
+    }
+    object.into()
+}
+
+#[wasm_bindgen(module = "/js/codeActionProvider.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = "makeCodeActionProvider")]
+    fn make_code_action_provider(provide: &JsValue) -> Object;
+}
+
 #[wasm_bindgen(module = "/js/completionItemProvider.js")]
 extern "C" {
-    #[wasm_bindgen(js_name = "completionItemsProvider")]
-    fn completion_items_provider() -> Object;
+    #[wasm_bindgen(js_name = "makeCompletionItemsProvider")]
+    fn make_completion_items_provider(provide: &JsValue) -> Object;
+}
+
+#[wasm_bindgen(module = "/js/formattingProvider.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = "makeFormattingProvider")]
+    fn make_formatting_provider(format: &JsValue) -> Object;
+}
+
+#[wasm_bindgen(module = "/js/hoverProvider.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = "makeHoverProvider")]
+    fn make_hover_provider(provide: &JsValue) -> Object;
+}
+
+#[wasm_bindgen(module = "/js/labelProviders.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = "makeDefinitionProvider")]
+    fn make_definition_provider(provide: &JsValue) -> Object;
+
+    #[wasm_bindgen(js_name = "makeReferenceProvider")]
+    fn make_reference_provider(provide: &JsValue) -> Object;
 }
 
 #[wasm_bindgen(module = "/js/monarchTokensProvider.js")]
 extern "C" {
     #[wasm_bindgen(js_name = "makeTokensProvider")]
-    fn make_tokens_provider() -> Object;
+    fn make_tokens_provider(keywords: &Array) -> Object;
+}
+
+#[wasm_bindgen(module = "/js/outlineProviders.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = "makeDocumentSymbolProvider")]
+    fn make_document_symbol_provider(provide: &JsValue) -> Object;
+
+    #[wasm_bindgen(js_name = "makeFoldingRangeProvider")]
+    fn make_folding_range_provider(provide: &JsValue) -> Object;
+}
+
+#[wasm_bindgen(module = "/js/renameProvider.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = "makeRenameProvider")]
+    fn make_rename_provider(resolve: &JsValue, rename: &JsValue) -> Object;
 }
 
 #[wasm_bindgen(module = "/js/theme.js")]
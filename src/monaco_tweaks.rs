@@ -2,22 +2,26 @@ use std::rc::Rc;
 
 use js_sys::{Array, Object};
 use monaco::{
-    api::{CodeEditor, DisposableClosure},
+    api::{CodeEditor, DisposableClosure, TextModel},
     sys::{
         editor::{
-            ICodeEditor, IEditorMouseEvent, IModelDecorationOptions, IModelDeltaDecoration,
-            IStandaloneCodeEditor, MouseTargetType,
+            self, ICodeEditor, IEditorMouseEvent, IMarkerData, IModelDecorationOptions,
+            IModelDeltaDecoration, IStandaloneCodeEditor, InjectedTextOptions, MouseTargetType,
         },
-        Selection,
+        MarkerSeverity, Selection,
     },
     sys::{IRange, Range},
 };
+use ramemu::registers::RegisterId;
 use wasm_bindgen::{JsCast, JsValue};
 use yewdux::Listener;
 
 use crate::{
     io::output::OutputComponentErrors,
-    store::{dispatch, Store},
+    language::{diagnostics::diagnostics, hover::inline_values, lints::lint},
+    monaco_ram::LANG_ID,
+    store::{dispatch, run_dispatch, Store},
+    syntax::{self, Line, Location},
 };
 
 #[derive(Default)]
@@ -25,6 +29,7 @@ pub struct EditorStoreListener {
     error_ids: Array,
     breakpoint_ids: Array,
     debug_line_ids: Array,
+    inline_value_ids: Array,
 }
 
 impl Listener for EditorStoreListener {
@@ -49,8 +54,16 @@ impl Listener for EditorStoreListener {
                 }
             }
 
-            let debug_line_ids = if state.current_debug_line != 0 {
-                debug_line_dec(state.current_debug_line as f64)
+            // An inspected step takes the highlight from the paused line
+            let debug_line = state.inspected_line().unwrap_or(state.current_debug_line);
+            let debug_line_ids = if debug_line != 0 {
+                debug_line_dec(debug_line as f64)
+            } else {
+                Array::new()
+            };
+
+            let inline_value_ids = if state.read_only && state.current_debug_line != 0 {
+                inline_values_dec(&state)
             } else {
                 Array::new()
             };
@@ -60,6 +73,8 @@ impl Listener for EditorStoreListener {
             self.error_ids = editor.delta_decorations(&self.error_ids, &error_ids);
             self.breakpoint_ids = editor.delta_decorations(&self.breakpoint_ids, &breakpoint_ids);
             self.debug_line_ids = editor.delta_decorations(&self.debug_line_ids, &debug_line_ids);
+            self.inline_value_ids =
+                editor.delta_decorations(&self.inline_value_ids, &inline_value_ids);
         });
     }
 }
@@ -89,6 +104,118 @@ pub fn setup_breakpoints(editor: &CodeEditor) -> DisposableClosure<dyn FnMut(IEd
     })
 }
 
+/// How many previously executed lines also show their values.
+const INLINE_VALUE_CONTEXT: usize = 3;
+/// Steps searched for them, so a long tight loop doesn't walk the whole run.
+const INLINE_VALUE_LOOKBACK: usize = 256;
+
+/// Shows the register values the paused line refers to after its text, and
+/// those the last few executed lines referred to when they ran.
+fn inline_values_dec(state: &Store) -> Array {
+    let new_decorations = Array::new();
+    let current = state.current_debug_line;
+
+    let model = state.get_model();
+    let code = model.get_value();
+    let lines: Vec<_> = syntax::scan(&code).collect();
+    let run = run_dispatch().get();
+    let history = &state.register_history;
+    let executed = history.executed();
+
+    // The latest step of each line, as a loop runs the same lines again
+    let mut shown = vec![current];
+    let mut previous = Vec::new();
+    let steps = (1..=executed.len()).rev().zip(executed.iter().rev());
+    for (step, &line) in steps.take(INLINE_VALUE_LOOKBACK) {
+        if previous.len() == INLINE_VALUE_CONTEXT {
+            break;
+        }
+        if !shown.contains(&line) {
+            shown.push(line);
+            previous.push((line, step));
+        }
+    }
+
+    if let Some(line) = lines.get(current - 1) {
+        let values = inline_values(line, |register| run.registers.get(RegisterId(register)));
+        if let Some(values) = values {
+            new_decorations.push(&inline_value_dec(
+                line,
+                &values,
+                "inline-value inline-value-current",
+            ));
+        }
+    }
+    for (line, step) in previous {
+        let Some(line) = lines.get(line - 1) else {
+            continue;
+        };
+        let values = inline_values(line, |register| history.value_at(register, step));
+        if let Some(values) = values {
+            let values = format!("{values} (step {step})");
+            new_decorations.push(&inline_value_dec(line, &values, "inline-value"));
+        }
+    }
+
+    new_decorations
+}
+
+fn inline_value_dec(line: &Line, values: &str, class_name: &str) -> IModelDeltaDecoration {
+    let after: InjectedTextOptions = Object::new().unchecked_into();
+    after.set_content(&format!("  {values}"));
+    after.set_inline_class_name(Some(class_name));
+
+    let options: IModelDecorationOptions = Object::new().unchecked_into();
+    options.set_after(Some(&after));
+
+    let end = syntax::column(line.text, line.text.len()) as f64;
+    let number = line.number as f64;
+    let irange: IRange = Range::new(number, end, number, end).unchecked_into();
+
+    let decoration: IModelDeltaDecoration = Object::new().unchecked_into();
+    decoration.set_options(&options);
+    decoration.set_range(&irange);
+    decoration
+}
+
+/// Owner of the lint markers, so they are replaced separately from parse errors.
+const LINTS_OWNER: &str = "ram-lints";
+
+/// Marks the parse errors of `model` the way Monaco marks problems, without
+/// waiting for a run. Lints are only shown for code that parses.
+pub fn publish_diagnostics(model: &TextModel) {
+    let code = model.get_value();
+    let errors = diagnostics(&code);
+    let warnings = if errors.is_empty() {
+        let disabled = dispatch().get().disabled_lints.clone();
+        lint(&code, |lint| !disabled.contains(&lint))
+    } else {
+        Vec::new()
+    };
+
+    let errors: Array = errors
+        .into_iter()
+        .map(|error| marker(error.location, &error.message, MarkerSeverity::Error))
+        .collect();
+    let warnings: Array = warnings
+        .into_iter()
+        .map(|warning| marker(warning.location, &warning.message, MarkerSeverity::Warning))
+        .collect();
+    editor::set_model_markers(model.as_ref(), LANG_ID, &errors);
+    editor::set_model_markers(model.as_ref(), LINTS_OWNER, &warnings);
+}
+
+fn marker(location: Location, message: &str, severity: MarkerSeverity) -> JsValue {
+    let marker: IMarkerData = Object::new().unchecked_into();
+    marker.set_severity(severity);
+    marker.set_message(message);
+    marker.set_start_line_number(location.line as f64);
+    marker.set_start_column(location.start_column as f64);
+    marker.set_end_line_number(location.line as f64);
+    marker.set_end_column(location.end_column as f64);
+    marker.into()
+}
+
 fn draw_error(error: &OutputComponentErrors) -> Array {
     let (kind, line, error_classname) = match &error {
         OutputComponentErrors::InterpretError(e) => (
@@ -101,6 +228,7 @@ fn draw_error(error: &OutputComponentErrors) -> Array {
             e.line as f64,
             "syntax-error-line-highlight",
         ),
+        OutputComponentErrors::InvalidInput(_) => return Array::new(),
     };
 
     let new_decorations = Array::new();
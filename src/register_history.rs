@@ -0,0 +1,111 @@
+use std::{
+    cell::{Ref, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use ramemu::registers::{RegisterId, Registers};
+
+use crate::{
+    monaco_ram::{instruction, Writes},
+    syntax::{self, Operand},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RegisterWrite {
+    pub step: usize,
+    pub line: usize,
+    pub value: i64,
+}
+
+/// Register the instruction on a line stores into.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Target {
+    Register(usize),
+    /// The register whose number is in this one when the instruction runs.
+    Pointer(usize),
+}
+
+/// Every executed line and every value written to each register during a run.
+///
+/// Clones share the recorded writes, so the runner can keep appending while
+/// the store holds a clone. Two clones are equal until one records more.
+#[derive(Clone, Default)]
+pub struct RegisterHistory {
+    targets: Rc<HashMap<usize, Target>>,
+    writes: Rc<RefCell<HashMap<usize, Vec<RegisterWrite>>>>,
+    /// Line executed by each step, the first step at index 0.
+    lines: Rc<RefCell<Vec<usize>>>,
+    recorded: usize,
+}
+
+impl PartialEq for RegisterHistory {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.writes, &other.writes) && self.recorded == other.recorded
+    }
+}
+
+impl RegisterHistory {
+    /// An empty history for a run of `code`.
+    pub fn new(code: &str) -> Self {
+        let targets = syntax::scan(code)
+            .filter_map(|line| {
+                let instruction = instruction(line.opcode?.text)?;
+                let target = match (instruction.writes, line.operand()) {
+                    (Writes::Accumulator, _) => Target::Register(0),
+                    (Writes::Operand, Some(Operand::Direct(register))) => {
+                        Target::Register(register)
+                    }
+                    (Writes::Operand, Some(Operand::Indirect(pointer))) => Target::Pointer(pointer),
+                    _ => return None,
+                };
+                Some((line.number, target))
+            })
+            .collect();
+        Self {
+            targets: Rc::new(targets),
+            ..Self::default()
+        }
+    }
+
+    /// The register the instruction on `line` is about to write, given the
+    /// registers before it runs.
+    pub fn target(&self, line: usize, registers: &Registers<i64>) -> Option<usize> {
+        match *self.targets.get(&line)? {
+            Target::Register(register) => Some(register),
+            Target::Pointer(pointer) => usize::try_from(registers.get(RegisterId(pointer))).ok(),
+        }
+    }
+
+    /// Records that the next step executed `line`.
+    pub fn record_step(&mut self, line: usize) {
+        self.lines.borrow_mut().push(line);
+        self.recorded += 1;
+    }
+
+    /// Records the value `register` holds after the step, even if it did not change.
+    pub fn record(&mut self, step: usize, line: usize, register: usize, value: i64) {
+        let write = RegisterWrite { step, line, value };
+        let mut writes = self.writes.borrow_mut();
+        writes.entry(register).or_default().push(write);
+        self.recorded += 1;
+    }
+
+    /// The value `register` held once `step` had run.
+    pub fn value_at(&self, register: usize, step: usize) -> i64 {
+        let writes = self.writes(register);
+        let written = writes.partition_point(|write| write.step <= step);
+        written.checked_sub(1).map_or(0, |last| writes[last].value)
+    }
+
+    /// Lines executed by the steps so far, in order.
+    pub fn executed(&self) -> Ref<'_, [usize]> {
+        Ref::map(self.lines.borrow(), Vec::as_slice)
+    }
+
+    pub fn writes(&self, register: usize) -> Ref<'_, [RegisterWrite]> {
+        Ref::map(self.writes.borrow(), |writes| {
+            writes.get(&register).map_or(&[][..], Vec::as_slice)
+        })
+    }
+}
@@ -0,0 +1,80 @@
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yewdux::use_selector;
+
+#[cfg(not(feature = "ssr"))]
+use crate::monaco_tweaks::publish_diagnostics;
+use crate::{
+    language::{format::KeywordCase, lints::Lint},
+    store::{dispatch, Store},
+};
+
+#[function_component(Settings)]
+pub fn settings() -> Html {
+    let format_options = *use_selector(|s: &Store| s.format_options);
+    let disabled_lints = use_selector(|s: &Store| s.disabled_lints.clone());
+
+    let handle_case_change = |event: Event| {
+        let Some(select) = event.target_dyn_into::<HtmlSelectElement>() else {
+            log::error!("Failed to cast event target to HtmlSelectElement");
+            return;
+        };
+        let keyword_case = match select.value().as_str() {
+            "lower" => KeywordCase::Lower,
+            _ => KeywordCase::Upper,
+        };
+        dispatch().reduce_mut(|s| s.format_options.keyword_case = keyword_case);
+    };
+
+    let toggle_format_on_save = |_: MouseEvent| {
+        dispatch().reduce_mut(|s| {
+            s.format_options.format_on_save = !s.format_options.format_on_save;
+        });
+    };
+
+    let lower = format_options.keyword_case == KeywordCase::Lower;
+
+    let lints = Lint::ALL.iter().map(|&lint| {
+        let toggle = move |_: MouseEvent| {
+            dispatch().reduce_mut(|s| {
+                if !s.disabled_lints.remove(&lint) {
+                    s.disabled_lints.insert(lint);
+                }
+            });
+            #[cfg(not(feature = "ssr"))]
+            publish_diagnostics(dispatch().get().get_model());
+        };
+        html! {
+          <label>
+            <input type="checkbox" checked={!disabled_lints.contains(&lint)} onclick={toggle} />
+            {lint.description()}
+          </label>
+        }
+    });
+
+    html! {
+      <details class="settings">
+        <summary>{"Settings"}</summary>
+        <div class="settings-panel">
+          <h3>{"Formatting (Shift+Alt+F)"}</h3>
+          <label>
+            {"Keywords "}
+            <select onchange={handle_case_change}>
+              <option value="upper" selected={!lower}>{"UPPERCASE"}</option>
+              <option value="lower" selected={lower}>{"lowercase"}</option>
+            </select>
+          </label>
+          <label>
+            <input
+              type="checkbox"
+              checked={format_options.format_on_save}
+              onclick={toggle_format_on_save}
+            />
+            {"Format on save (Ctrl+S)"}
+          </label>
+          <h3>{"Warnings"}</h3>
+          { for lints }
+        </div>
+      </details>
+    }
+}
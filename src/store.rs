@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 use monaco::api::TextModel;
 #[cfg(not(feature = "ssr"))]
@@ -7,16 +7,31 @@ use ramemu::registers::Registers;
 use serde::{Deserialize, Serialize};
 use yewdux::prelude::*;
 
-use crate::{code_editor::DEFAULT_CODE, io::output::OutputComponentErrors};
+use crate::{
+    code_editor::DEFAULT_CODE,
+    io::{io_mode::IoMode, output::OutputComponentErrors},
+    language::{format::FormatOptions, lints::Lint},
+    register_history::RegisterHistory,
+};
 
 #[cfg(feature = "ssr")]
-pub fn dispatch() -> Dispatch<Store> {
+fn context() -> yewdux::Context {
     use yewdux::Context;
     thread_local! {
         static CONTEXT: Context = Default::default();
     }
 
-    Dispatch::new(&CONTEXT.with(|context| context.clone()))
+    CONTEXT.with(|context| context.clone())
+}
+
+#[cfg(feature = "ssr")]
+pub fn dispatch() -> Dispatch<Store> {
+    Dispatch::new(&context())
+}
+
+#[cfg(feature = "ssr")]
+pub fn run_dispatch() -> Dispatch<RunStore> {
+    Dispatch::new(&context())
 }
 
 #[cfg(not(feature = "ssr"))]
@@ -24,6 +39,11 @@ pub fn dispatch() -> Dispatch<Store> {
     Dispatch::global()
 }
 
+#[cfg(not(feature = "ssr"))]
+pub fn run_dispatch() -> Dispatch<RunStore> {
+    Dispatch::global()
+}
+
 #[derive(Default, PartialEq, Store, Clone, Serialize, Deserialize)]
 #[store(storage = "local")]
 pub struct Store {
@@ -37,14 +57,47 @@ pub struct Store {
     #[serde(skip)]
     pub current_debug_line: usize,
     #[serde(skip)]
-    registers: Registers<i64>,
-    #[serde(skip)]
     pub copy_button_state: Option<bool>,
+    #[serde(skip)]
+    pub register_history: RegisterHistory,
+    #[serde(skip)]
+    pub pinned_registers: Option<Registers<i64>>,
+    #[serde(skip)]
+    pub pointer_registers: Vec<usize>,
+    #[serde(skip)]
+    pub allow_invalid_stdin: bool,
+    /// Step whose register values are shown instead of the current ones.
+    #[serde(skip)]
+    pub inspected_step: Option<usize>,
+    /// Line of the last executed instruction and the line execution continued on.
+    #[serde(skip)]
+    pub last_step: Option<(usize, usize)>,
 
     #[cfg(not(feature = "ssr"))]
     text_model: TextModelWrapper,
     pub breakpoints: HashSet<usize>,
     pub stdin: String,
+    #[serde(default)]
+    pub stdin_presets: BTreeMap<String, String>,
+    #[serde(default)]
+    pub stdin_preset: Option<String>,
+    #[serde(default)]
+    pub io_mode: IoMode,
+    #[serde(default)]
+    pub format_options: FormatOptions,
+    #[serde(default)]
+    pub disabled_lints: BTreeSet<Lint>,
+}
+
+/// What a run changes on every step. It lives apart from [`Store`], which is
+/// saved to local storage on every change, along with the code and stdin.
+#[derive(Default, PartialEq, Store, Clone)]
+pub struct RunStore {
+    pub registers: Registers<i64>,
+    /// Stdin values read so far, `None` until a run starts or after stdin changes.
+    pub stdin_position: Option<usize>,
+    /// Whether a run is stopped on a line, as opposed to running or not started.
+    pub paused: bool,
 }
 
 impl Store {
@@ -56,11 +109,37 @@ impl Store {
     pub fn change_model(&mut self) {
         self.text_model.1 = self.text_model.1.wrapping_add(1);
     }
-    pub fn get_registers(&self) -> &Registers<i64> {
-        &self.registers
+    /// Changes whenever the code does, for selectors that depend on it.
+    #[cfg(not(feature = "ssr"))]
+    pub fn model_version(&self) -> u64 {
+        self.text_model.1
+    }
+    /// Line of the step being inspected, if any.
+    pub fn inspected_line(&self) -> Option<usize> {
+        let step = self.inspected_step?;
+        let executed = self.register_history.executed();
+        executed.get(step.checked_sub(1)?).copied()
+    }
+    /// Replaces stdin with an edited one, which no longer matches the selected preset.
+    pub fn set_stdin(&mut self, stdin: String) {
+        self.stdin = stdin;
+        self.stdin_preset = None;
+    }
+    pub fn save_stdin_preset(&mut self, name: String) {
+        self.stdin_presets.insert(name.clone(), self.stdin.clone());
+        self.stdin_preset = Some(name);
+    }
+    pub fn load_stdin_preset(&mut self, name: &str) {
+        let Some(stdin) = self.stdin_presets.get(name) else {
+            return;
+        };
+        self.stdin = stdin.clone();
+        self.stdin_preset = Some(name.to_owned());
     }
-    pub fn set_registers(&mut self, registers: Registers<i64>) {
-        self.registers = registers;
+    pub fn delete_stdin_preset(&mut self) {
+        if let Some(name) = self.stdin_preset.take() {
+            self.stdin_presets.remove(&name);
+        }
     }
 }
 
@@ -0,0 +1,259 @@
+//! Lightweight scanner over RAM source, used by tooling that has to look at the
+//! program text itself rather than at what `ramemu::parser::parse` produces.
+//!
+//! A line has the shape `[label:] [opcode [operand]] [# comment]`.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    /// Byte offset of the token in its line.
+    pub start: usize,
+}
+
+impl Token<'_> {
+    pub fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+
+    pub fn contains(&self, offset: usize) -> bool {
+        (self.start..self.end()).contains(&offset)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Operand<'a> {
+    /// `=n`
+    Constant(i64),
+    /// `n`
+    Direct(usize),
+    /// `*n`
+    Indirect(usize),
+    Label(&'a str),
+}
+
+impl<'a> Operand<'a> {
+    pub fn parse(text: &'a str) -> Option<Self> {
+        if let Some(value) = text.strip_prefix('=') {
+            value.parse().ok().map(Self::Constant)
+        } else if let Some(register) = text.strip_prefix('*') {
+            register.parse().ok().map(Self::Indirect)
+        } else if text.starts_with(|c: char| c.is_ascii_digit()) {
+            text.parse().ok().map(Self::Direct)
+        } else {
+            is_label(text).then_some(Self::Label(text))
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Line<'a> {
+    /// 1-based, as in Monaco and `ramemu` errors.
+    pub number: usize,
+    pub text: &'a str,
+    /// Label name without the trailing `:`.
+    pub label: Option<Token<'a>>,
+    pub opcode: Option<Token<'a>>,
+    pub operand: Option<Token<'a>>,
+    /// Everything between the operand and the comment.
+    pub rest: Option<Token<'a>>,
+    pub comment: Option<Token<'a>>,
+}
+
+impl<'a> Line<'a> {
+    pub fn operand(&self) -> Option<Operand<'a>> {
+        self.operand
+            .and_then(|operand| Operand::parse(operand.text))
+    }
+
+    /// Everything but the comment, or `None` for a blank line.
+    pub fn code(&self) -> Option<Token<'a>> {
+        let first = self.label.or(self.opcode)?;
+        let last = [self.label, self.opcode, self.operand, self.rest]
+            .into_iter()
+            .flatten()
+            .last()?;
+        Some(Token {
+            text: &self.text[first.start..last.end()],
+            start: first.start,
+        })
+    }
+}
+
+/// A token on a 1-based line, with 1-based columns and an exclusive end.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Location {
+    pub line: usize,
+    pub start_column: usize,
+    pub end_column: usize,
+}
+
+impl Location {
+    pub fn new(line: &Line, token: Token) -> Self {
+        Self {
+            line: line.number,
+            start_column: column(line.text, token.start),
+            end_column: column(line.text, token.end()),
+        }
+    }
+}
+
+pub fn is_label(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Converts a byte offset in `line` into a 1-based Monaco column.
+pub fn column(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+/// Converts a 1-based Monaco column into a byte offset in `line`.
+pub fn offset(line: &str, column: usize) -> usize {
+    line.char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(line.len(), |(offset, _)| offset)
+}
+
+/// Registers dereferenced by `*n` operands anywhere in `code`, sorted and deduplicated.
+pub fn pointer_registers(code: &str) -> Vec<usize> {
+    let mut registers: Vec<_> = scan(code)
+        .filter_map(|line| match line.operand()? {
+            Operand::Indirect(register) => Some(register),
+            _ => None,
+        })
+        .collect();
+    registers.sort_unstable();
+    registers.dedup();
+    registers
+}
+
+pub fn scan(code: &str) -> impl Iterator<Item = Line<'_>> {
+    code.lines()
+        .enumerate()
+        .map(|(index, text)| scan_line(index + 1, text))
+}
+
+pub fn scan_line(number: usize, text: &str) -> Line<'_> {
+    let mut line = Line {
+        number,
+        text,
+        ..Default::default()
+    };
+
+    let code_end = match text.find('#') {
+        Some(start) => {
+            line.comment = Some(Token {
+                text: &text[start..],
+                start,
+            });
+            start
+        }
+        None => text.len(),
+    };
+
+    let mut tokens = tokens(&text[..code_end]).peekable();
+
+    if let Some(first) = tokens.peek() {
+        if let Some(label) = first.text.strip_suffix(':') {
+            line.label = Some(Token {
+                text: label,
+                start: first.start,
+            });
+            tokens.next();
+        }
+    }
+
+    line.opcode = tokens.next();
+    line.operand = tokens.next();
+    line.rest = tokens.next().map(|first| {
+        let end = text[..code_end].trim_end().len();
+        Token {
+            text: &text[first.start..end],
+            start: first.start,
+        }
+    });
+
+    line
+}
+
+fn tokens(text: &str) -> impl Iterator<Item = Token<'_>> {
+    text.split_whitespace().map(move |token| Token {
+        text: token,
+        start: token.as_ptr() as usize - text.as_ptr() as usize,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts<'a>(line: &Line<'a>) -> [Option<&'a str>; 5] {
+        [
+            line.label,
+            line.opcode,
+            line.operand,
+            line.rest,
+            line.comment,
+        ]
+        .map(|token| token.map(|token| token.text))
+    }
+
+    #[test]
+    fn scans_every_part_of_a_line() {
+        let line = scan_line(3, "  loop: LOAD *2 extra stuff  # comment");
+        assert_eq!(line.number, 3);
+        assert_eq!(
+            texts(&line),
+            [
+                Some("loop"),
+                Some("LOAD"),
+                Some("*2"),
+                Some("extra stuff"),
+                Some("# comment")
+            ]
+        );
+        assert_eq!(line.label.unwrap().start, 2);
+        assert_eq!(line.code().unwrap().text, "loop: LOAD *2 extra stuff");
+    }
+
+    #[test]
+    fn scans_partial_lines() {
+        assert_eq!(texts(&scan_line(1, "")), [None; 5]);
+        assert_eq!(scan_line(1, "   # only").code(), None);
+        assert_eq!(
+            texts(&scan_line(1, "end:")),
+            [Some("end"), None, None, None, None]
+        );
+        assert_eq!(
+            texts(&scan_line(1, "HALT#done")),
+            [None, Some("HALT"), None, None, Some("#done")]
+        );
+    }
+
+    #[test]
+    fn parses_operands() {
+        assert_eq!(Operand::parse("=-5"), Some(Operand::Constant(-5)));
+        assert_eq!(Operand::parse("12"), Some(Operand::Direct(12)));
+        assert_eq!(Operand::parse("*3"), Some(Operand::Indirect(3)));
+        assert_eq!(Operand::parse("_loop2"), Some(Operand::Label("_loop2")));
+        assert_eq!(Operand::parse("*-1"), None);
+        assert_eq!(Operand::parse("2x"), None);
+        assert_eq!(Operand::parse("="), None);
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        let text = "ñ: HALT";
+        assert_eq!(column(text, 0), 1);
+        assert_eq!(column(text, text.find('H').unwrap()), 4);
+        assert_eq!(offset(text, 4), text.find('H').unwrap());
+        assert_eq!(offset(text, 100), text.len());
+    }
+
+    #[test]
+    fn pointer_registers_are_sorted_and_unique() {
+        let code = "LOAD *3\nSTORE *1\nADD *3\nSUB 2";
+        assert_eq!(pointer_registers(code), [1, 3]);
+    }
+}
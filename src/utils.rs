@@ -16,6 +16,9 @@ use monaco::{
 
 use yew::prelude::*;
 
+#[cfg(not(feature = "ssr"))]
+use crate::language::format::{format, FormatOptions};
+
 #[function_component]
 pub fn HydrationGate(props: &Props) -> Html {
     let is_hydrated = use_state(|| false);
@@ -102,6 +105,26 @@ pub fn comment_code(editor: &CodeEditor, model: &TextModel) -> Result<(), ()> {
     Ok(())
 }
 
+#[cfg(not(feature = "ssr"))]
+/// Replaces the code with its formatted version as a single undoable edit.
+pub fn format_code(editor: &CodeEditor, model: &TextModel, options: FormatOptions) {
+    let code = model.get_value();
+    let formatted = format(&code, options);
+    if formatted == code {
+        return;
+    }
+
+    let itext_model: &ITextModel = model.as_ref();
+    let edits = Array::new();
+    let edit: IIdentifiedSingleEditOperation = Object::new().unchecked_into();
+    edit.set_range(&itext_model.get_full_model_range().unchecked_into());
+    edit.set_text(Some(&formatted));
+    edits.push(&edit);
+
+    let ieditor: &ICodeEditor = editor.as_ref();
+    ieditor.execute_edits("format", &edits, None);
+}
+
 pub fn get_author() -> Option<String> {
     gloo::utils::window()
         .location()
@@ -121,8 +144,45 @@ pub fn get_author() -> Option<String> {
         })
 }
 
+/// Reveals `line` and puts the cursor at its start.
+#[cfg(not(feature = "ssr"))]
+pub fn jump_to_line(line: usize) {
+    use crate::store::dispatch;
+
+    let editor = dispatch().get().editor.clone();
+    editor.with_editor(|editor| {
+        let ieditor: &IEditor = editor.as_ref();
+        let position = Position::new(line as f64, 1.);
+        ieditor.set_position(position.unchecked_ref());
+        ieditor.reveal_line_in_center(line as f64, None);
+        ieditor.focus();
+    });
+}
+
+/// Selects the lines `first..=last` in full and reveals them.
+#[cfg(not(feature = "ssr"))]
+pub fn select_lines(first: usize, last: usize) {
+    use crate::store::dispatch;
+
+    let store = dispatch().get();
+    let itext_model: &ITextModel = store.get_model().as_ref();
+    let end_column = itext_model.get_line_max_column(last as f64);
+    store.editor.with_editor(|editor| {
+        let ieditor: &IEditor = editor.as_ref();
+        let range = Range::new(first as f64, 1., last as f64, end_column);
+        ieditor.set_selection(range.unchecked_ref());
+        ieditor.reveal_lines_in_center(first as f64, last as f64, None);
+        ieditor.focus();
+    });
+}
+
 #[cfg(not(feature = "ssr"))]
 pub fn download_code(content: &str) -> Result<(), JsValue> {
+    download_file(content, "project.ram")
+}
+
+#[cfg(not(feature = "ssr"))]
+pub fn download_file(content: &str, file_name: &str) -> Result<(), JsValue> {
     let document = gloo::utils::document();
     let body = gloo::utils::body();
 
@@ -135,7 +195,7 @@ pub fn download_code(content: &str) -> Result<(), JsValue> {
         urlencoding::encode(content)
     );
     element.set_href(&href);
-    element.set_download("project.ram");
+    element.set_download(file_name);
     element.style().set_property("display", "none")?;
 
     body.append_child(&element)?;